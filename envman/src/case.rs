@@ -0,0 +1,117 @@
+use std::{cell::RefCell, collections::HashMap, env::VarError, ffi::OsString};
+
+use crate::{def::indices_of, def::names_of, EnvManError, EnvSource};
+
+/// [`EnvSource`] matching the keys of another source ignoring case, used by `#[envman(case_insensitive)]`
+///
+/// The keys of the source are read once when it is created. If a key is set with several cases,
+/// the exact match is used, or else the first key in sorted order. Such keys are remembered when
+/// they are read, and [`CaseInsensitiveSource::take_ambiguities`] reports them as errors.
+///
+/// # Example
+/// ```rust
+/// use envman::{env_map, CaseInsensitiveSource, EnvSource};
+///
+/// let source = env_map! { "Db_Url" => "mysql://localhost" };
+/// let source = CaseInsensitiveSource::new(&source);
+/// assert_eq!(source.var("DB_URL").unwrap(), "mysql://localhost");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CaseInsensitiveSource<'a, S: ?Sized> {
+    source: &'a S,
+    /// Keys of the source by their lowercase form, sorted
+    keys: HashMap<String, Vec<String>>,
+    /// Keys read that are also set with other cases, in the order they were first read
+    ambiguous: RefCell<Vec<String>>,
+}
+
+impl<'a, S: EnvSource + ?Sized> CaseInsensitiveSource<'a, S> {
+    /// Wrap `source`, reading its keys
+    pub fn new(source: &'a S) -> Self {
+        let mut keys: HashMap<String, Vec<String>> = HashMap::new();
+        for key in source.keys() {
+            keys.entry(key.to_lowercase()).or_default().push(key);
+        }
+        for found in keys.values_mut() {
+            found.sort_unstable();
+        }
+        Self {
+            source,
+            keys,
+            ambiguous: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// `EnvManError::AmbiguousKey` for each key read so far that is also set with other cases,
+    /// including the entries of `nest, map` and `nest, indexed` fields
+    pub fn take_ambiguities(&self) -> Vec<EnvManError> {
+        self.ambiguous
+            .take()
+            .into_iter()
+            .filter_map(|key| {
+                let found = self.keys.get(&key.to_lowercase())?.clone();
+                Some(EnvManError::AmbiguousKey { key, found })
+            })
+            .collect()
+    }
+
+    /// Key of the source matching `key`
+    fn resolve(&self, key: &str) -> Option<&str> {
+        let found = self.keys.get(&key.to_lowercase())?;
+        let resolved = match found.iter().find(|found| *found == key) {
+            Some(found) => found,
+            None => found.first()?,
+        };
+        if found.len() > 1 {
+            let mut ambiguous = self.ambiguous.borrow_mut();
+            if !ambiguous.contains(resolved) {
+                ambiguous.push(resolved.clone());
+            }
+        }
+        Some(resolved)
+    }
+
+    /// Keys of the source in lowercase
+    fn lowercase_keys(&self) -> Vec<String> {
+        self.keys.keys().cloned().collect()
+    }
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for CaseInsensitiveSource<'_, S> {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        self.source.var_os(self.resolve(key)?)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.keys.values().flatten().cloned().collect()
+    }
+
+    fn var(&self, key: &str) -> Result<String, VarError> {
+        match self.resolve(key) {
+            Some(key) => self.source.var(key),
+            None => Err(VarError::NotPresent),
+        }
+    }
+
+    // Entries are matched in lowercase, so the names of `nest, map` entries are lowercase
+    fn indices(&self, prefix: &str, separator: &str) -> Vec<usize> {
+        indices_of(
+            &self.lowercase_keys(),
+            &prefix.to_lowercase(),
+            &separator.to_lowercase(),
+        )
+    }
+
+    fn names(&self, prefix: &str, separator: &str, fields: &[String]) -> Vec<String> {
+        let fields = fields
+            .iter()
+            .map(|field| field.to_lowercase())
+            .collect::<Vec<_>>();
+        names_of(
+            &self.lowercase_keys(),
+            &prefix.to_lowercase(),
+            &separator.to_lowercase(),
+            &fields,
+        )
+    }
+}
@@ -1,6 +1,244 @@
+use std::{env::VarError, ffi::OsString};
+
 pub trait EnvMan: Sized {
     /// Load environment variables
-    fn load_from_env() -> Result<Self, EnvManError>;
+    fn load_from_env() -> Result<Self, EnvManError> {
+        Self::load_from(&ProcessEnv)
+    }
+
+    /// Load environment variables from the given [`EnvSource`]
+    fn load_from<S: EnvSource + ?Sized>(source: &S) -> Result<Self, EnvManError>;
+
+    /// Load environment variables as a `nest` field of another struct
+    ///
+    /// The default implementation ignores `context` and calls [`EnvMan::load_from`].
+    fn load_nested<S: EnvSource + ?Sized>(
+        source: &S,
+        context: &NestContext,
+    ) -> Result<Self, EnvManError> {
+        let _ = context;
+        Self::load_from(source)
+    }
+
+    /// Whether any of the variables of this struct is set in `source`
+    ///
+    /// An `Option` or `default` nest field falls back to `None` or its default only when this is false.
+    /// The default implementation always returns true.
+    fn is_present<S: EnvSource + ?Sized>(source: &S, context: &NestContext) -> bool {
+        let _ = (source, context);
+        true
+    }
+
+    /// Keys of the variables read by this struct
+    ///
+    /// Entries of `nest, map` fields are discovered by matching these keys.
+    /// The default implementation returns no keys.
+    fn key_names(context: &NestContext) -> Vec<String> {
+        let _ = context;
+        Vec::new()
+    }
+
+    /// Key of a `nest, map` entry named `name` in the variables
+    ///
+    /// The derive converts `name` from the `rename_all` rule back to snake_case.
+    /// The default implementation returns `name` unchanged.
+    fn map_key(name: &str) -> String {
+        name.to_owned()
+    }
+}
+
+/// Key naming context passed from a struct to its `nest` fields
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NestContext {
+    parent_prefix: String,
+    prefix: Option<String>,
+    nesting_separator: Option<String>,
+}
+
+impl NestContext {
+    /// Context for the `nest` field `field` of a struct whose effective prefix is `parent_prefix`.
+    ///
+    /// `prefix` is the prefix given on the field, which replaces the prefix of the nested struct.
+    /// With a `nesting_separator`, the nested struct is prefixed with `parent_prefix`,
+    /// followed by `prefix` or else `field` and the separator.
+    pub fn nest(
+        &self,
+        parent_prefix: &str,
+        nesting_separator: Option<&str>,
+        field: &str,
+        prefix: Option<&str>,
+    ) -> Self {
+        match nesting_separator {
+            Some(separator) => Self {
+                parent_prefix: String::new(),
+                prefix: Some(match prefix {
+                    Some(prefix) => format!("{parent_prefix}{prefix}"),
+                    None => format!("{parent_prefix}{field}{separator}"),
+                }),
+                nesting_separator: Some(separator.to_owned()),
+            },
+            None => Self {
+                parent_prefix: parent_prefix.to_owned(),
+                prefix: prefix.map(ToOwned::to_owned),
+                nesting_separator: None,
+            },
+        }
+    }
+
+    /// Context for an element of a `nest, indexed` field, whose keys start with `prefix`
+    pub fn element(&self, prefix: String, nesting_separator: Option<&str>) -> Self {
+        Self {
+            parent_prefix: String::new(),
+            prefix: Some(prefix),
+            nesting_separator: nesting_separator.map(ToOwned::to_owned),
+        }
+    }
+
+    /// Keys read by `T` as an entry of a `nest, map` field, relative to the prefix of the entry
+    pub fn relative_keys<T: EnvMan>(&self, nesting_separator: Option<&str>) -> Vec<String> {
+        // Keys that do not start with the marker do not depend on the entry
+        const MARKER: &str = "\0";
+        T::key_names(&self.element(MARKER.to_owned(), nesting_separator))
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(MARKER).map(ToOwned::to_owned))
+            .collect()
+    }
+
+    /// Separator for hierarchical keys, inherited from the parent struct or else `nesting_separator`
+    pub fn nesting_separator<'a>(&'a self, nesting_separator: Option<&'a str>) -> Option<&'a str> {
+        self.nesting_separator.as_deref().or(nesting_separator)
+    }
+
+    /// Effective prefix of a struct whose own prefix is `prefix`.
+    ///
+    /// If `inherit` is true, the prefix of the parent struct is prepended.
+    pub fn resolve_prefix(&self, prefix: &str, inherit: bool) -> String {
+        let prefix = self.prefix.as_deref().unwrap_or(prefix);
+        if inherit {
+            format!("{}{prefix}", self.parent_prefix)
+        } else {
+            prefix.to_owned()
+        }
+    }
+}
+
+/// Source of environment variables used by [`EnvMan::load_from`]
+pub trait EnvSource {
+    /// Fetch the variable named `key`, with the same semantics as [`std::env::var_os`]
+    fn var_os(&self, key: &str) -> Option<OsString>;
+
+    /// Names of all variables in the source, skipping names that are not valid unicode
+    fn keys(&self) -> Vec<String>;
+
+    /// Fetch the variable named `key`, with the same semantics as [`std::env::var`]
+    fn var(&self, key: &str) -> Result<String, VarError> {
+        match self.var_os(key) {
+            Some(value) => value.into_string().map_err(VarError::NotUnicode),
+            None => Err(VarError::NotPresent),
+        }
+    }
+
+    /// Sorted indices `i` for which a variable starting with `{prefix}{i}{separator}` is set
+    ///
+    /// Indices with leading zeros such as `01` are skipped, as they do not name the key read for the element.
+    fn indices(&self, prefix: &str, separator: &str) -> Vec<usize> {
+        indices_of(&self.keys(), prefix, separator)
+    }
+
+    /// Sorted names `n` for which a variable `{prefix}{n}{separator}{field}` is set, for any of `fields`
+    ///
+    /// When several fields match a key, the longest one is used.
+    fn names(&self, prefix: &str, separator: &str, fields: &[String]) -> Vec<String> {
+        names_of(&self.keys(), prefix, separator, fields)
+    }
+}
+
+/// [`EnvSource::indices`] of a source with the variables `keys`
+pub(crate) fn indices_of(keys: &[String], prefix: &str, separator: &str) -> Vec<usize> {
+    let mut indices = keys
+        .iter()
+        .filter_map(|key| {
+            let rest = key.strip_prefix(prefix)?;
+            let end = rest.find(|c: char| !c.is_ascii_digit())?;
+            // `01` would be read back as `1`, from a key nobody set
+            if end == 0 || (end > 1 && rest.starts_with('0')) || !rest[end..].starts_with(separator)
+            {
+                return None;
+            }
+            rest[..end].parse().ok()
+        })
+        .collect::<Vec<usize>>();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// [`EnvSource::names`] of a source with the variables `keys`
+pub(crate) fn names_of(
+    keys: &[String],
+    prefix: &str,
+    separator: &str,
+    fields: &[String],
+) -> Vec<String> {
+    let mut names = keys
+        .iter()
+        .filter_map(|key| {
+            let rest = key.strip_prefix(prefix)?;
+            fields
+                .iter()
+                .filter_map(|field| {
+                    let name = rest.strip_suffix(field.as_str())?.strip_suffix(separator)?;
+                    (!name.is_empty()).then_some(name)
+                })
+                .min_by_key(|name| name.len())
+                .map(ToOwned::to_owned)
+        })
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for &S {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        (**self).var_os(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        (**self).keys()
+    }
+
+    fn var(&self, key: &str) -> Result<String, VarError> {
+        (**self).var(key)
+    }
+
+    fn indices(&self, prefix: &str, separator: &str) -> Vec<usize> {
+        (**self).indices(prefix, separator)
+    }
+
+    fn names(&self, prefix: &str, separator: &str, fields: &[String]) -> Vec<String> {
+        (**self).names(prefix, separator, fields)
+    }
+}
+
+/// [`EnvSource`] reading the environment of the current process
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessEnv;
+
+impl EnvSource for ProcessEnv {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        std::env::var_os(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        std::env::vars_os()
+            .filter_map(|(key, _)| key.into_string().ok())
+            .collect()
+    }
+
+    fn var(&self, key: &str) -> Result<String, VarError> {
+        std::env::var(key)
+    }
 }
 
 /// Error type for [`EnvMan`]
@@ -8,14 +246,33 @@ pub trait EnvMan: Sized {
 pub enum EnvManError {
     /// Failed to read environment variable
     #[error("failed to read environment variable '{key}'")]
-    NotFound { key: &'static str },
+    NotFound { key: String },
+
+    /// Environment variable is present but not valid unicode
+    #[error("environment variable '{key}' is not valid unicode")]
+    NotUnicode { key: String },
+
+    /// Environment variable is set to an empty value, with `empty = "error"`
+    #[error("environment variable '{key}' is empty")]
+    Empty { key: String },
 
     /// Failed to parse environment variable
-    #[error("failed to parse environment variable '{key}' with value '{value}' (expected type: {expected_type})")]
+    #[error(
+        "failed to parse {}environment variable '{key}' with value '{value}' for field `{struct_name}::{field}` (expected type: {})",
+        format_element_index(.element),
+        format_expected_type(.expected_type, .element)
+    )]
     Parse {
-        key: &'static str,
+        key: String,
         value: String,
+        /// Type of the field, without `Option`
         expected_type: &'static str,
+        /// Name of the field
+        field: &'static str,
+        /// Name of the struct owning the field
+        struct_name: &'static str,
+        /// The element that failed, for `separator` fields, boxed to keep the error small
+        element: Option<Box<ParseElement>>,
         #[source]
         source: Box<dyn std::error::Error>,
     },
@@ -23,16 +280,281 @@ pub enum EnvManError {
     /// Failed validation for environment variable
     #[error("validation failed for environment variable '{key}' with value '{value}': {message}")]
     Validation {
-        key: &'static str,
+        key: String,
+        value: String,
+        message: String,
+    },
+
+    /// Environment variable is missing while a `required_if` or `required_unless` condition requires it
+    #[error("environment variable '{key}' is required {condition}")]
+    Required {
+        key: String,
+        /// The condition requiring the variable, e.g. "when 'TLS_ENABLED' is 'true'"
+        condition: String,
+    },
+
+    /// Environment variable is set several times with keys differing only in case, with `case_insensitive`
+    #[error("environment variable '{key}' is ambiguous, found {}", .found.join(", "))]
+    AmbiguousKey { key: String, found: Vec<String> },
+
+    /// Environment variable is set together with a variable named by `conflicts_with`
+    #[error("environment variables '{key}' and '{other}' cannot both be set")]
+    Conflict { key: String, other: String },
+
+    /// Number of set variables of a `group` breaks its rule
+    #[error("{rule} of the environment variables in group '{group}' must be set ({}), found {}", .keys.join(", "), format_set(.set))]
+    Group {
+        group: &'static str,
+        rule: GroupRule,
+        /// Keys of all members of the group
+        keys: Vec<String>,
+        /// Keys of the members that are set
+        set: Vec<String>,
+    },
+
+    /// Failed `validate_struct` of a struct
+    #[error("validation failed for `{struct_name}`{}: {message}", format_keys(.keys))]
+    StructValidation {
+        struct_name: &'static str,
+        /// Keys of the fields named by the validation
+        keys: Vec<String>,
+        message: String,
+    },
+
+    /// Failed `validate_each`, `unique` or `sorted` for an element of a `separator` field
+    #[error("validation failed for element {index} of environment variable '{key}' with value '{value}': {message}")]
+    ElementValidation {
+        key: String,
+        /// Position of the element in the list, starting at 0
+        index: usize,
+        /// Text of the element
         value: String,
         message: String,
     },
 
+    /// Value of a `list_syntax = "quoted"` field is not a valid list
+    #[error("environment variable '{key}' with value '{value}' is not a valid list: {message}")]
+    ListSyntax {
+        key: String,
+        value: String,
+        message: String,
+    },
+
+    /// Element of a `kv_separator` field without the `kv_separator`
+    #[error("element {index} of environment variable '{key}' with value '{value}' is not a pair separated by '{kv_separator}'")]
+    MalformedPair {
+        key: String,
+        /// Position of the element in the list, starting at 0
+        index: usize,
+        /// Text of the element
+        value: String,
+        kv_separator: &'static str,
+    },
+
+    /// Element of a `tuple_separator` field with the wrong number of parts
+    #[error("element {index} of environment variable '{key}' with value '{value}' has {found} parts separated by '{tuple_separator}', expected {expected}")]
+    TupleArity {
+        key: String,
+        /// Position of the element in the list, starting at 0
+        index: usize,
+        /// Text of the element
+        value: String,
+        tuple_separator: &'static str,
+        found: usize,
+        expected: usize,
+    },
+
+    /// Key repeated in a `kv_separator` field
+    #[error("element {index} of environment variable '{key}' repeats the key '{entry_key}'")]
+    DuplicateKey {
+        key: String,
+        /// Position of the element in the list, starting at 0
+        index: usize,
+        /// Text of the repeated key
+        entry_key: String,
+    },
+
+    /// Value of the `tag` variable of an enum matches none of its variants
+    #[error("environment variable '{key}' has unknown value '{value}', expected one of: {}", .expected.join(", "))]
+    UnknownVariant {
+        key: String,
+        value: String,
+        expected: &'static [&'static str],
+    },
+
+    /// Indices of a `nest, indexed` field are not contiguous
+    #[error("missing index {index} of environment variable '{key}', indices must start at 0 without gaps")]
+    IndexGap { key: String, index: usize },
+
+    /// Wrong number of entries
+    #[error("environment variable '{key}' has {found} entries, expected {}", format_count(*.min, *.max))]
+    Count {
+        key: String,
+        found: usize,
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+
+    /// Error in a `nest` field
+    #[error("{path}: {error}")]
+    Nested {
+        /// Path of `nest` fields leading to the error, joined by `.`
+        path: String,
+        error: Box<EnvManError>,
+    },
+
     /// Multiple errors occurred while loading environment variables
     #[error("multiple errors occurred while loading environment variables:\n{}", format_errors(.0))]
     Multiple(Vec<EnvManError>),
 }
 
+/// Element of a `separator` field that failed to parse, see [`EnvManError::Parse`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseElement {
+    /// Position of the element in the list, starting at 0
+    pub index: usize,
+    /// Position in the element, for `kv_separator`, `tuple_separator` and `inner_separator` fields
+    pub position: Option<usize>,
+    /// Type of the element, or of the part at `position`
+    pub expected_type: &'static str,
+}
+
+/// Error of the `FromStr` implementation generated by `EnvManEnum`
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown value '{value}', expected one of: {}", .expected.join(", "))]
+pub struct ParseEnumError {
+    /// The value that matched no variant
+    pub value: String,
+    /// Values accepted by the enum, including aliases
+    pub expected: &'static [&'static str],
+}
+
+/// Rule of a `group` struct attribute, see [`EnvManError::Group`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupRule {
+    ExactlyOne,
+    AtLeastOne,
+    AtMostOne,
+}
+
+impl std::fmt::Display for GroupRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            GroupRule::ExactlyOne => "exactly one",
+            GroupRule::AtLeastOne => "at least one",
+            GroupRule::AtMostOne => "at most one",
+        })
+    }
+}
+
+/// Error returned by a `validate_struct` function, see [`EnvManError::StructValidation`]
+///
+/// ```
+/// # use envman::StructValidationError;
+/// let err = StructValidationError::new("min_pool must not exceed max_pool")
+///     .field("min_pool")
+///     .field("max_pool");
+/// assert_eq!(err.fields, ["min_pool", "max_pool"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructValidationError {
+    /// Names of the fields involved, reported as their keys
+    pub fields: Vec<&'static str>,
+    pub message: String,
+}
+
+impl StructValidationError {
+    pub fn new(message: impl std::fmt::Display) -> Self {
+        Self {
+            fields: Vec::new(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Name a field involved in the failure
+    pub fn field(mut self, field: &'static str) -> Self {
+        self.fields.push(field);
+        self
+    }
+}
+
+impl From<String> for StructValidationError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for StructValidationError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl EnvManError {
+    /// Key of the environment variable the error is about, if it is about a single one
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            EnvManError::NotFound { key }
+            | EnvManError::NotUnicode { key }
+            | EnvManError::Empty { key }
+            | EnvManError::Parse { key, .. }
+            | EnvManError::Validation { key, .. }
+            | EnvManError::ElementValidation { key, .. }
+            | EnvManError::ListSyntax { key, .. }
+            | EnvManError::MalformedPair { key, .. }
+            | EnvManError::TupleArity { key, .. }
+            | EnvManError::DuplicateKey { key, .. }
+            | EnvManError::UnknownVariant { key, .. }
+            | EnvManError::Required { key, .. }
+            | EnvManError::Conflict { key, .. }
+            | EnvManError::AmbiguousKey { key, .. }
+            | EnvManError::IndexGap { key, .. }
+            | EnvManError::Count { key, .. } => Some(key),
+            EnvManError::StructValidation { keys, .. } if keys.len() == 1 => Some(&keys[0]),
+            EnvManError::StructValidation { .. } | EnvManError::Group { .. } => None,
+            EnvManError::Nested { error, .. } => error.key(),
+            EnvManError::Multiple(_) => None,
+        }
+    }
+
+    /// Mark the error as coming from the `nest` field `field`
+    pub fn nested(self, field: &str) -> Self {
+        match self {
+            EnvManError::Multiple(errors) => {
+                EnvManError::Multiple(errors.into_iter().map(|e| e.nested(field)).collect())
+            }
+            EnvManError::Nested { path, error } => EnvManError::Nested {
+                path: format!("{field}.{path}"),
+                error,
+            },
+            error => EnvManError::Nested {
+                path: field.to_owned(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Combine the errors of several fields into one error.
+    ///
+    /// A single error is returned as is, and nested [`EnvManError::Multiple`] are flattened.
+    pub fn from_errors(errors: Vec<EnvManError>) -> Self {
+        let mut flattened = Vec::with_capacity(errors.len());
+        for err in errors {
+            match err {
+                EnvManError::Multiple(inner) => flattened.extend(inner),
+                err => flattened.push(err),
+            }
+        }
+
+        if flattened.len() == 1 {
+            if let Some(err) = flattened.pop() {
+                return err;
+            }
+        }
+        EnvManError::Multiple(flattened)
+    }
+}
+
 fn format_errors(errors: &[EnvManError]) -> String {
     errors
         .iter()
@@ -41,3 +563,48 @@ fn format_errors(errors: &[EnvManError]) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+fn format_set(set: &[String]) -> String {
+    if set.is_empty() {
+        String::from("none")
+    } else {
+        set.join(", ")
+    }
+}
+
+fn format_keys(keys: &[String]) -> String {
+    if keys.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", keys.join(", "))
+    }
+}
+
+fn format_element_index(element: &Option<Box<ParseElement>>) -> String {
+    match element.as_deref() {
+        Some(ParseElement {
+            index,
+            position: Some(position),
+            ..
+        }) => format!("position {position} of element {index} of "),
+        Some(element) => format!("element {} of ", element.index),
+        None => String::new(),
+    }
+}
+
+fn format_expected_type(expected_type: &str, element: &Option<Box<ParseElement>>) -> String {
+    match element {
+        Some(element) => format!("{}, element of {expected_type}", element.expected_type),
+        None => expected_type.to_owned(),
+    }
+}
+
+fn format_count(min: Option<usize>, max: Option<usize>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) if min == max => format!("exactly {min}"),
+        (Some(min), Some(max)) => format!("between {min} and {max}"),
+        (Some(min), None) => format!("at least {min}"),
+        (None, Some(max)) => format!("at most {max}"),
+        (None, None) => String::from("any number"),
+    }
+}
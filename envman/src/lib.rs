@@ -8,7 +8,18 @@
 )]
 
 #[cfg(feature = "derive")]
-pub use envman_derive::{EnvMan, EnvManDebug};
+pub use envman_derive::{EnvMan, EnvManDebug, EnvManEnum};
 
 mod def;
 pub use def::*;
+
+mod map;
+pub use map::*;
+
+mod case;
+pub use case::*;
+
+mod list;
+pub use list::*;
+
+pub mod validators;
@@ -0,0 +1,184 @@
+/// Split a list written in the `list_syntax = "quoted"` syntax.
+///
+/// Items are separated by `separator` and trimmed, except for the parts in `"double"` or
+/// `'single'` quotes. A backslash escapes a quote, another backslash, or outside quotes the
+/// separator. Any other backslash is kept, so that patterns such as `\d+` need no escaping.
+/// Empty items are kept, and an empty value is an empty list.
+///
+/// ```
+/// # use envman::split_list;
+/// assert_eq!(split_list(r#"a, "b, c",,d\,e"#, ",").unwrap(), ["a", "b, c", "", "d,e"]);
+/// assert_eq!(split_list(r#""^\d+$", '\w', [a-z]+"#, ",").unwrap(), [r"^\d+$", r"\w", "[a-z]+"]);
+/// assert!(split_list(r#""a, b"#, ",").is_err());
+/// ```
+pub fn split_list(value: &str, separator: &str) -> Result<Vec<String>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut items = Vec::new();
+    // Characters of the current item, and whether they are quoted or escaped
+    let mut item: Vec<(char, bool)> = Vec::new();
+    let mut quote: Option<(char, usize)> = None;
+    let mut chars = value.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        match (c, quote) {
+            ('\\', _) if value[offset + 1..].starts_with(['\\', '"', '\'']) => {
+                if let Some((_, escaped)) = chars.next() {
+                    item.push((escaped, true));
+                }
+            }
+            ('\\', None) if value[offset + 1..].starts_with(separator) => {
+                item.extend(separator.chars().map(|c| (c, true)));
+                for _ in 0..separator.chars().count() {
+                    chars.next();
+                }
+            }
+            ('\\', _) => item.push((c, true)),
+            (c, Some((open, _))) if c == open => quote = None,
+            (c, Some(_)) => item.push((c, true)),
+            ('"' | '\'', None) => quote = Some((c, offset)),
+            (_, None) if value[offset..].starts_with(separator) => {
+                items.push(finish_item(&item));
+                item.clear();
+                // Skip the rest of a separator longer than one character
+                for _ in 1..separator.chars().count() {
+                    chars.next();
+                }
+            }
+            (c, None) => item.push((c, false)),
+        }
+    }
+    if let Some((open, offset)) = quote {
+        return Err(format!("unterminated {open} starting at byte {offset}"));
+    }
+    items.push(finish_item(&item));
+    Ok(items)
+}
+
+/// Split a list written in the `list_syntax = "json"` syntax.
+///
+/// A value starting with `[` is read as a JSON array. Strings are unescaped, and numbers,
+/// `true`, `false` and `null` are kept as written. Other values are split with [`split_list`].
+///
+/// ```
+/// # use envman::split_json_list;
+/// assert_eq!(split_json_list(r#"["a,b", "c", 1]"#, ",").unwrap(), ["a,b", "c", "1"]);
+/// assert_eq!(split_json_list("a, b", ",").unwrap(), ["a", "b"]);
+/// ```
+pub fn split_json_list(value: &str, separator: &str) -> Result<Vec<String>, String> {
+    let value = value.trim();
+    if value.starts_with('[') {
+        return JsonArray { rest: value }.parse();
+    }
+    split_list(value, separator)
+}
+
+/// Trim the whitespace that is neither quoted nor escaped
+fn finish_item(item: &[(char, bool)]) -> String {
+    let blank = |&(c, protected): &(char, bool)| !protected && c.is_whitespace();
+    let start = item.iter().position(|c| !blank(c)).unwrap_or(item.len());
+    let end = item
+        .iter()
+        .rposition(|c| !blank(c))
+        .map_or(start, |i| i + 1);
+    item[start..end].iter().map(|(c, _)| c).collect()
+}
+
+struct JsonArray<'a> {
+    rest: &'a str,
+}
+
+impl JsonArray<'_> {
+    fn parse(mut self) -> Result<Vec<String>, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        if !self.eat(']') {
+            loop {
+                items.push(self.item()?);
+                if self.eat(']') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        if !self.rest.trim().is_empty() {
+            return Err(String::from("unexpected text after the JSON array"));
+        }
+        Ok(items)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(format!("expected '{c}' in the JSON array"))
+        }
+    }
+
+    fn item(&mut self) -> Result<String, String> {
+        if self.eat('"') {
+            return self.string();
+        }
+        let end = self
+            .rest
+            .find(|c: char| c == ',' || c == ']' || c.is_whitespace())
+            .unwrap_or(self.rest.len());
+        let (item, rest) = self.rest.split_at(end);
+        if item.is_empty() || item.starts_with(['[', '{']) {
+            return Err(String::from(
+                "JSON array items must be strings, numbers, booleans or null",
+            ));
+        }
+        self.rest = rest;
+        Ok(item.to_string())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let mut string = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[offset + 1..];
+                    return Ok(string);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| {
+                                    format!("invalid escape \\u{hex} in the JSON array")
+                                })?
+                        }
+                        Some(c @ ('"' | '\\' | '/')) => c,
+                        _ => return Err(String::from("invalid escape in the JSON array")),
+                    };
+                    string.push(escaped);
+                }
+                c => string.push(c),
+            }
+        }
+        Err(String::from("unterminated string in the JSON array"))
+    }
+}
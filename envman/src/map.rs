@@ -0,0 +1,108 @@
+use std::{collections::HashMap, ffi::OsString};
+
+use crate::EnvSource;
+
+/// In-memory [`EnvSource`], useful for loading configuration without touching the process environment
+///
+/// # Example
+/// ```rust
+/// use envman::{env_map, EnvMan};
+///
+/// #[derive(EnvMan)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// let source = env_map! { "PORT" => "8080" };
+/// let config = Config::load_from(&source).unwrap();
+/// assert_eq!(config.port, 8080);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MapSource {
+    vars: HashMap<String, OsString>,
+}
+
+impl MapSource {
+    /// Create an empty source
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the variable `key`, returning its previous value
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<OsString>,
+    ) -> Option<OsString> {
+        self.vars.insert(key.into(), value.into())
+    }
+
+    /// Unset the variable `key`, returning its previous value
+    pub fn remove(&mut self, key: &str) -> Option<OsString> {
+        self.vars.remove(key)
+    }
+}
+
+impl EnvSource for MapSource {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        self.vars.get(key).cloned()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.vars.keys().cloned().collect()
+    }
+}
+
+impl From<HashMap<String, String>> for MapSource {
+    fn from(vars: HashMap<String, String>) -> Self {
+        vars.into_iter().collect()
+    }
+}
+
+impl From<HashMap<String, OsString>> for MapSource {
+    fn from(vars: HashMap<String, OsString>) -> Self {
+        Self { vars }
+    }
+}
+
+impl<K: Into<String>, V: Into<OsString>> FromIterator<(K, V)> for MapSource {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            vars: iter
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<OsString>> Extend<(K, V)> for MapSource {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.vars
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v.into())));
+    }
+}
+
+/// Create a [`MapSource`] from `key => value` pairs
+///
+/// ```rust
+/// let source = envman::env_map! {
+///     "DB_URL" => "mysql://localhost",
+///     "DB_PORT" => "3306",
+/// };
+/// ```
+#[macro_export]
+macro_rules! env_map {
+    () => {
+        $crate::MapSource::new()
+    };
+    ($($key:expr => $value:expr),+ $(,)?) => {
+        <$crate::MapSource as ::std::iter::FromIterator<(
+            ::std::string::String,
+            ::std::ffi::OsString,
+        )>>::from_iter([$((
+            ::std::convert::Into::<::std::string::String>::into($key),
+            ::std::convert::Into::<::std::ffi::OsString>::into($value),
+        )),+])
+    };
+}
@@ -0,0 +1,154 @@
+//! Validators behind the built-in validation attributes such as `range` and `url`
+//!
+//! Each one returns a message describing what the value must be, reported as
+//! [`EnvManError::Validation`](crate::EnvManError::Validation). They can also be used
+//! as the body of a custom `validate` function.
+
+use std::fmt::Display;
+
+/// Values with a length, for `len` and `non_empty`
+pub trait Length {
+    /// Number of characters of strings, or number of elements of collections
+    fn length(&self) -> usize;
+}
+
+impl Length for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// `range(min = 1, max = 1024)`: the value is within the bounds, inclusive
+pub fn range<T: PartialOrd + Display>(
+    value: &T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<(), String> {
+    match (min, max) {
+        (Some(min), Some(max)) if *value < min || *value > max => {
+            Err(format!("must be between {min} and {max}"))
+        }
+        (Some(min), None) if *value < min => Err(format!("must be at least {min}")),
+        (None, Some(max)) if *value > max => Err(format!("must be at most {max}")),
+        _ => Ok(()),
+    }
+}
+
+/// `len(min = 1, max = 64)`: the length of the value is within the bounds, inclusive
+pub fn len<T: Length + ?Sized>(
+    value: &T,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> Result<(), String> {
+    let length = value.length();
+    match (min, max) {
+        (Some(min), Some(max)) if length < min || length > max => Err(format!(
+            "length must be between {min} and {max}, got {length}"
+        )),
+        (Some(min), None) if length < min => {
+            Err(format!("length must be at least {min}, got {length}"))
+        }
+        (None, Some(max)) if length > max => {
+            Err(format!("length must be at most {max}, got {length}"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `non_empty`: the value has a length other than 0
+pub fn non_empty<T: Length + ?Sized>(value: &T) -> Result<(), String> {
+    if value.length() == 0 {
+        Err(String::from("must not be empty"))
+    } else {
+        Ok(())
+    }
+}
+
+/// `one_of = ["a", "b"]`: the value, formatted with `Display`, is one of `allowed`
+pub fn one_of<T: Display + ?Sized>(value: &T, allowed: &[&str]) -> Result<(), String> {
+    let value = value.to_string();
+    if allowed.iter().any(|v| *v == value) {
+        Ok(())
+    } else {
+        Err(format!("must be one of: {}", allowed.join(", ")))
+    }
+}
+
+/// `matches = r"^[a-z]+$"`: the value matches the regular expression
+#[cfg(feature = "regex")]
+pub fn matches<T: AsRef<str> + ?Sized>(value: &T, pattern: &str) -> Result<(), String> {
+    let regex = regex::Regex::new(pattern).map_err(|err| format!("invalid pattern: {err}"))?;
+    if regex.is_match(value.as_ref()) {
+        Ok(())
+    } else {
+        Err(format!("must match the pattern `{pattern}`"))
+    }
+}
+
+/// `url`: the value looks like `scheme://host...`
+pub fn url<T: AsRef<str> + ?Sized>(value: &T) -> Result<(), String> {
+    let value = value.as_ref();
+    let valid = match value.split_once("://") {
+        Some((scheme, rest)) => {
+            let mut scheme_chars = scheme.chars();
+            let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+            scheme_chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+                && scheme_chars.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+                && !host.is_empty()
+                && !value.chars().any(char::is_whitespace)
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(String::from("must be a URL such as `https://example.com`"))
+    }
+}
+
+/// `email`: the value looks like `local@domain.tld`
+pub fn email<T: AsRef<str> + ?Sized>(value: &T) -> Result<(), String> {
+    let value = value.as_ref();
+    let valid = match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !value.chars().any(char::is_whitespace)
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(String::from("must be an email address"))
+    }
+}
+
+/// `port`: the value is a port number between 1 and 65535
+pub fn port<T: Copy + TryInto<u16>>(value: &T) -> Result<(), String> {
+    match (*value).try_into() {
+        Ok(port) if port != 0 => Ok(()),
+        _ => Err(String::from("must be a port number between 1 and 65535")),
+    }
+}
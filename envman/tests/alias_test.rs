@@ -0,0 +1,38 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "APP_")]
+#[allow(dead_code)]
+struct Config {
+    #[envman(alias = "DATABASE_URL", alias = "PG_URL")]
+    db_url: String,
+    #[envman(alias = "PORT", default = 8080)]
+    port: u16,
+}
+
+#[test]
+fn falls_back_to_aliases_in_order() {
+    let source = env_map! { "APP_DB_URL" => "primary", "DATABASE_URL" => "vendor", "PORT" => "80" };
+    let config = Config::load_from(&source).unwrap();
+    assert_eq!(config.db_url, "primary");
+    assert_eq!(config.port, 80);
+
+    let source = env_map! { "PG_URL" => "pg", "DATABASE_URL" => "vendor" };
+    let config = Config::load_from(&source).unwrap();
+    assert_eq!(config.db_url, "vendor");
+    assert_eq!(config.port, 8080);
+
+    let config = Config::load_from(&env_map! { "PG_URL" => "pg" }).unwrap();
+    assert_eq!(config.db_url, "pg");
+}
+
+#[test]
+fn errors_name_the_resolved_key() {
+    let source = env_map! { "APP_DB_URL" => "a", "PORT" => "http" };
+    let err = Config::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::Parse { .. }));
+    assert_eq!(err.key(), Some("PORT"));
+
+    let err = Config::load_from(&env_map! {}).unwrap_err();
+    assert_eq!(err.key(), Some("APP_DB_URL"));
+}
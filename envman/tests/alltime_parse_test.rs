@@ -3,7 +3,7 @@ use std::{
     str::FromStr,
 };
 
-use envman::EnvMan;
+use envman::{env_map, EnvMan};
 
 #[derive(EnvMan, Debug, PartialEq)]
 struct TestNormal {
@@ -18,11 +18,13 @@ struct TestNormal {
 
 #[test]
 fn normal() {
-    std::env::set_var("DB_URL", "mysql://example");
-    std::env::set_var("DB_IP", "127.0.0.1:80");
+    let source = env_map! {
+        "DB_URL" => "mysql://example",
+        "DB_IP" => "127.0.0.1:80",
+    };
 
     assert_eq!(
-        TestNormal::load_from_env().unwrap(),
+        TestNormal::load_from(&source).unwrap(),
         TestNormal {
             db_url: String::from("mysql://example"),
             db_ip: SocketAddr::from_str("127.0.0.1:80").unwrap(),
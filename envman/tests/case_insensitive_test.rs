@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "APP_", case_insensitive)]
+#[allow(dead_code)]
+struct Config {
+    db_url: String,
+    #[envman(default = 8080)]
+    port: u16,
+    #[envman(nest)]
+    cache: Cache,
+    #[envman(nest, map, default)]
+    queues: HashMap<String, Queue>,
+}
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct Cache {
+    cache_size: usize,
+}
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct Queue {
+    workers: u8,
+}
+
+#[test]
+fn matches_keys_ignoring_case() {
+    let source = env_map! {
+        "app_db_url" => "mysql://localhost",
+        "App_Port" => "80",
+        "Cache_Size" => "64",
+        "app_QUEUES_Mail_WORKERS" => "2",
+    };
+    let config = Config::load_from(&source).unwrap();
+    assert_eq!(config.db_url, "mysql://localhost");
+    assert_eq!(config.port, 80);
+    assert_eq!(config.cache.cache_size, 64);
+    assert_eq!(config.queues["mail"].workers, 2);
+}
+
+#[test]
+fn reports_ambiguous_keys() {
+    let source = env_map! {
+        "APP_DB_URL" => "mysql://a",
+        "app_db_url" => "mysql://b",
+        "CACHE_SIZE" => "64",
+    };
+    let err = Config::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::AmbiguousKey { .. }));
+    assert_eq!(err.key(), Some("APP_DB_URL"));
+    assert_eq!(
+        err.to_string(),
+        "environment variable 'APP_DB_URL' is ambiguous, found APP_DB_URL, app_db_url"
+    );
+}
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct Queues {
+    #[envman(nest, map)]
+    queues: HashMap<String, Queue>,
+}
+
+#[test]
+fn forwards_entry_discovery_through_references() {
+    let source = env_map! { "queues_mail_workers" => "2" };
+    let source = envman::CaseInsensitiveSource::new(&source);
+    let config = Queues::load_from(&&source).unwrap();
+    assert_eq!(config.queues["mail"].workers, 2);
+}
+
+#[test]
+fn reports_ambiguous_entry_keys() {
+    let source = env_map! {
+        "APP_DB_URL" => "mysql://localhost",
+        "CACHE_SIZE" => "64",
+        "APP_QUEUES_MAIL_WORKERS" => "1",
+        "app_queues_mail_workers" => "2",
+    };
+    let err = Config::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::AmbiguousKey { .. }));
+    assert_eq!(
+        err.to_string(),
+        "environment variable 'APP_QUEUES_MAIL_WORKERS' is ambiguous, \
+         found APP_QUEUES_MAIL_WORKERS, app_queues_mail_workers"
+    );
+}
+
+#[test]
+fn reports_ambiguous_keys_with_other_errors() {
+    let source = env_map! {
+        "APP_DB_URL" => "mysql://a",
+        "app_db_url" => "mysql://b",
+        "APP_PORT" => "http",
+    };
+    let err = Config::load_from(&source).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected several errors, got {err:?}");
+    };
+    // The ambiguous APP_DB_URL, the invalid APP_PORT and the missing CACHE_SIZE
+    assert_eq!(errors.len(), 3);
+    assert!(errors
+        .iter()
+        .any(|err| matches!(err, EnvManError::AmbiguousKey { .. })));
+    assert!(errors
+        .iter()
+        .any(|err| matches!(err, EnvManError::Parse { .. })));
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(tag = "KIND", case_insensitive)]
+#[allow(dead_code)]
+enum Storage {
+    Memory,
+    Disk(Cache),
+}
+
+#[test]
+fn reports_ambiguous_keys_of_variants() {
+    let source = env_map! {
+        "kind" => "disk",
+        "CACHE_SIZE" => "64",
+        "cache_size" => "32",
+    };
+    let err = Storage::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::AmbiguousKey { .. }));
+    assert_eq!(err.key(), Some("CACHE_SIZE"));
+}
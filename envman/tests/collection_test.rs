@@ -0,0 +1,67 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct CollectionConfig {
+    #[envman(separator = ",")]
+    hosts: HashSet<String>,
+    #[envman(separator = ",")]
+    ports: BTreeSet<u16>,
+    #[envman(separator = ";", default = VecDeque::new())]
+    queue: VecDeque<u8>,
+    #[envman(separator = ",", unique)]
+    tags: Box<[String]>,
+    #[envman(separator = ",")]
+    origin: Option<[f32; 3]>,
+}
+
+#[test]
+fn collects_into_field_type() {
+    let source = env_map! {
+        "HOSTS" => "a,b,a",
+        "PORTS" => "443, 80,443",
+        "QUEUE" => "3;1;2",
+        "TAGS" => "x,y",
+        "ORIGIN" => "0.5,1,2",
+    };
+    let config = CollectionConfig::load_from(&source).unwrap();
+    assert_eq!(
+        config.hosts,
+        HashSet::from([String::from("a"), String::from("b")])
+    );
+    assert_eq!(config.ports.into_iter().collect::<Vec<_>>(), vec![80, 443]);
+    assert_eq!(config.queue, VecDeque::from([3, 1, 2]));
+    assert_eq!(&*config.tags, ["x", "y"]);
+    assert_eq!(config.origin, Some([0.5, 1.0, 2.0]));
+}
+
+#[test]
+fn element_errors_name_element_type() {
+    let source = env_map! { "HOSTS" => "a", "PORTS" => "80,http", "TAGS" => "x" };
+    let err = CollectionConfig::load_from(&source).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to parse element 1 of environment variable 'PORTS' with value 'http' for field `CollectionConfig::ports` (expected type: u16, element of alloc::collections::btree::set::BTreeSet<u16>)"
+    );
+}
+
+#[test]
+fn array_requires_exact_length() {
+    let source = env_map! { "HOSTS" => "a", "PORTS" => "80", "TAGS" => "x", "ORIGIN" => "1,2" };
+    let err = CollectionConfig::load_from(&source).unwrap_err();
+    assert!(matches!(
+        err,
+        EnvManError::Count {
+            found: 2,
+            min: Some(3),
+            max: Some(3),
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "environment variable 'ORIGIN' has 2 entries, expected exactly 3"
+    );
+}
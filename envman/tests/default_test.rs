@@ -1,4 +1,4 @@
-use envman::EnvMan;
+use envman::{env_map, EnvMan};
 
 #[derive(EnvMan, Debug, PartialEq)]
 struct TestDefault {
@@ -12,7 +12,7 @@ struct TestDefault {
 #[test]
 fn default() {
     assert_eq!(
-        TestDefault::load_from_env().unwrap(),
+        TestDefault::load_from(&env_map! {}).unwrap(),
         TestDefault {
             redis_url: String::from("redis://example"),
             redis_max_conn: 5
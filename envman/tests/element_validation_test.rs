@@ -0,0 +1,88 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+#[allow(clippy::ptr_arg)]
+fn validate_origin(origin: &String) -> Result<(), String> {
+    if origin.starts_with("https://") {
+        Ok(())
+    } else {
+        Err(String::from("must start with https://"))
+    }
+}
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct ListConfig {
+    #[envman(separator = ",", validate_each = validate_origin, unique, default = Vec::new())]
+    allowed_origins: Vec<String>,
+    #[envman(separator = ",", min_items = 1, max_items = 3, unique)]
+    kafka_brokers: Vec<String>,
+    #[envman(separator = ",", sorted, default = Vec::new())]
+    retry_delays: Vec<u32>,
+}
+
+fn error(source: &envman::MapSource) -> EnvManError {
+    ListConfig::load_from(source).unwrap_err()
+}
+
+#[test]
+fn accepts_valid_lists() {
+    let source = env_map! {
+        "ALLOWED_ORIGINS" => "https://a.example, https://b.example",
+        "KAFKA_BROKERS" => "kafka-1:9092,kafka-2:9092",
+        "RETRY_DELAYS" => "1,1,5,30",
+    };
+    let config = ListConfig::load_from(&source).unwrap();
+    assert_eq!(config.allowed_origins.len(), 2);
+    assert_eq!(config.retry_delays, vec![1, 1, 5, 30]);
+}
+
+#[test]
+fn reports_failing_element() {
+    let source = env_map! {
+        "ALLOWED_ORIGINS" => "https://a.example,http://b.example",
+        "KAFKA_BROKERS" => "kafka-1:9092",
+    };
+    let err = error(&source);
+    assert!(matches!(
+        err,
+        EnvManError::ElementValidation { index: 1, ref value, .. } if value == "http://b.example"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "validation failed for element 1 of environment variable 'ALLOWED_ORIGINS' with value 'http://b.example': must start with https://"
+    );
+}
+
+#[test]
+fn unique_and_sorted() {
+    let source = env_map! { "KAFKA_BROKERS" => "a:9092,b:9092,a:9092" };
+    assert_eq!(
+        error(&source).to_string(),
+        "validation failed for element 2 of environment variable 'KAFKA_BROKERS' with value 'a:9092': duplicate of element 0"
+    );
+
+    let source = env_map! { "KAFKA_BROKERS" => "a:9092", "RETRY_DELAYS" => "1,5, 2" };
+    assert_eq!(
+        error(&source).to_string(),
+        "validation failed for element 2 of environment variable 'RETRY_DELAYS' with value ' 2': must not be less than element 1"
+    );
+}
+
+#[test]
+fn number_of_items() {
+    let source = env_map! { "KAFKA_BROKERS" => "a,b,c,d" };
+    let err = error(&source);
+    assert!(matches!(
+        err,
+        EnvManError::Count {
+            found: 4,
+            min: Some(1),
+            max: Some(3),
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "environment variable 'KAFKA_BROKERS' has 4 entries, expected between 1 and 3"
+    );
+}
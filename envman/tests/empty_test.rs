@@ -0,0 +1,97 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug)]
+#[envman(empty = "unset", trim)]
+#[allow(dead_code)]
+struct DefaultsConfig {
+    #[envman(default = 8080)]
+    port: u16,
+    log_level: Option<String>,
+    #[envman(empty = "error")]
+    host: String,
+    #[envman(empty = "value", trim = false, default = "x")]
+    motto: String,
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(empty = "error")]
+#[allow(dead_code)]
+struct StrictConfig {
+    port: u16,
+    #[envman(separator = ",", default = Vec::new())]
+    tags: Vec<String>,
+}
+
+#[test]
+fn empty_values_use_the_default() {
+    let source = env_map! {
+        "PORT" => "",
+        "LOG_LEVEL" => "   ",
+        "HOST" => " example.com ",
+        "MOTTO" => "",
+    };
+    let config = DefaultsConfig::load_from(&source).unwrap();
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.log_level, None);
+    assert_eq!(config.host, "example.com");
+    assert_eq!(config.motto, "");
+
+    let source = env_map! { "PORT" => " 80\n", "HOST" => "h", "MOTTO" => " hi " };
+    let config = DefaultsConfig::load_from(&source).unwrap();
+    assert_eq!(config.port, 80);
+    assert_eq!(config.motto, " hi ");
+}
+
+#[test]
+fn empty_values_are_rejected() {
+    let err = DefaultsConfig::load_from(&env_map! { "HOST" => "  " }).unwrap_err();
+    assert!(matches!(err, EnvManError::Empty { .. }));
+    assert_eq!(err.key(), Some("HOST"));
+    assert_eq!(err.to_string(), "environment variable 'HOST' is empty");
+
+    let err = StrictConfig::load_from(&env_map! { "PORT" => "1", "TAGS" => "" }).unwrap_err();
+    assert_eq!(err.key(), Some("TAGS"));
+
+    // Whitespace is only ignored with `trim`
+    let err = StrictConfig::load_from(&env_map! { "PORT" => " " }).unwrap_err();
+    assert!(matches!(err, EnvManError::Parse { .. }));
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(empty = "unset", group(name = "auth", exactly_one = [api_key, token]))]
+#[allow(dead_code)]
+struct ConditionConfig {
+    api_key: Option<String>,
+    token: Option<String>,
+    #[envman(required_unless = "HOST")]
+    url: Option<String>,
+    host: Option<String>,
+    #[envman(conflicts_with = "KEY_FILE")]
+    key: Option<String>,
+    #[envman(trim)]
+    key_file: Option<String>,
+}
+
+#[test]
+fn empty_values_are_unset_for_conditions() {
+    let source = env_map! {
+        "API_KEY" => "",
+        "TOKEN" => "t",
+        "URL" => "http://localhost",
+        "KEY" => "k",
+        "KEY_FILE" => " ",
+    };
+    let config = ConditionConfig::load_from(&source).unwrap();
+    assert_eq!(config.api_key, None);
+    assert_eq!(config.key.as_deref(), Some("k"));
+    assert_eq!(config.key_file, None);
+
+    let source = env_map! { "TOKEN" => "t", "HOST" => "" };
+    let err = ConditionConfig::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::Required { .. }));
+    assert_eq!(err.key(), Some("URL"));
+
+    let source = env_map! { "API_KEY" => "a", "TOKEN" => "t", "HOST" => "h" };
+    let err = ConditionConfig::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::Group { ref set, .. } if set == &["API_KEY", "TOKEN"]));
+}
@@ -0,0 +1,96 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(tag = "STORAGE_KIND")]
+enum Storage {
+    S3(S3Config),
+    #[envman(rename = "fs")]
+    Local(LocalConfig),
+    Memory,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "S3_")]
+struct S3Config {
+    bucket: String,
+    region: String,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "LOCAL_")]
+struct LocalConfig {
+    path: String,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(tag = "MODE", prefix = "CACHE_", rename_all = "kebab-case")]
+enum Cache {
+    ReadThrough,
+    #[envman(default)]
+    Disabled,
+}
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct AppConfig {
+    #[envman(nest)]
+    storage: Storage,
+}
+
+#[test]
+fn selects_variant_by_tag() {
+    let source = env_map! {
+        "STORAGE_KIND" => "s3",
+        "S3_BUCKET" => "assets",
+        "S3_REGION" => "eu-west-1",
+    };
+    assert_eq!(
+        Storage::load_from(&source).unwrap(),
+        Storage::S3(S3Config {
+            bucket: String::from("assets"),
+            region: String::from("eu-west-1"),
+        })
+    );
+
+    let source = env_map! { "STORAGE_KIND" => "FS", "LOCAL_PATH" => "/var/data" };
+    assert_eq!(
+        Storage::load_from(&source).unwrap(),
+        Storage::Local(LocalConfig {
+            path: String::from("/var/data"),
+        })
+    );
+
+    let source = env_map! { "STORAGE_KIND" => "MEMORY" };
+    assert_eq!(Storage::load_from(&source).unwrap(), Storage::Memory);
+}
+
+#[test]
+fn default_variant_and_rename_all() {
+    let source = env_map! {};
+    assert_eq!(Cache::load_from(&source).unwrap(), Cache::Disabled);
+
+    let source = env_map! { "CACHE_MODE" => "read-through" };
+    assert_eq!(Cache::load_from(&source).unwrap(), Cache::ReadThrough);
+}
+
+#[test]
+fn reports_missing_and_unknown_tag() {
+    let err = Storage::load_from(&env_map! {}).unwrap_err();
+    assert!(matches!(err, EnvManError::NotFound { .. }));
+    assert_eq!(err.key(), Some("STORAGE_KIND"));
+
+    let err = Storage::load_from(&env_map! { "STORAGE_KIND" => "gcs" }).unwrap_err();
+    assert!(matches!(err, EnvManError::UnknownVariant { .. }));
+    assert_eq!(
+        err.to_string(),
+        "environment variable 'STORAGE_KIND' has unknown value 'gcs', expected one of: S3, fs, MEMORY"
+    );
+}
+
+#[test]
+fn payload_errors_are_nested() {
+    let source = env_map! { "STORAGE_KIND" => "s3", "S3_BUCKET" => "assets" };
+    let err = AppConfig::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::Nested { ref path, .. } if path == "storage"));
+    assert_eq!(err.key(), Some("S3_REGION"));
+}
@@ -0,0 +1,75 @@
+use std::error::Error;
+
+use envman::{env_map, EnvMan, EnvManEnum, EnvManError, ParseEnumError};
+
+#[derive(EnvManEnum, Debug, PartialEq)]
+#[envman(rename_all = "kebab-case")]
+enum LogFormat {
+    Json,
+    #[envman(alias = "text", alias = "human")]
+    PlainText,
+    #[envman(rename = "bunyan")]
+    Structured,
+}
+
+#[derive(EnvManEnum, Debug, PartialEq)]
+enum Level {
+    Debug,
+    Info,
+}
+
+#[derive(EnvMan, Debug)]
+struct LogConfig {
+    log_format: LogFormat,
+    #[envman(default = Level::Info)]
+    log_level: Level,
+}
+
+#[test]
+fn parses_names_and_aliases() {
+    assert_eq!("json".parse(), Ok(LogFormat::Json));
+    assert_eq!("plain-text".parse(), Ok(LogFormat::PlainText));
+    assert_eq!("HUMAN".parse(), Ok(LogFormat::PlainText));
+    assert_eq!("Bunyan".parse(), Ok(LogFormat::Structured));
+    assert_eq!("DEBUG".parse(), Ok(Level::Debug));
+    assert_eq!("info".parse(), Ok(Level::Info));
+}
+
+#[test]
+fn unknown_value_lists_accepted_values() {
+    let err = "yaml".parse::<LogFormat>().unwrap_err();
+    assert_eq!(
+        err,
+        ParseEnumError {
+            value: String::from("yaml"),
+            expected: &["json", "plain-text", "text", "human", "bunyan"],
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "unknown value 'yaml', expected one of: json, plain-text, text, human, bunyan"
+    );
+}
+
+#[test]
+fn loads_as_field() {
+    let source = env_map! { "LOG_FORMAT" => "text" };
+    let config = LogConfig::load_from(&source).unwrap();
+    assert_eq!(config.log_format, LogFormat::PlainText);
+    assert_eq!(config.log_level, Level::Info);
+
+    let source = env_map! { "LOG_FORMAT" => "yaml", "LOG_LEVEL" => "trace" };
+    let err = LogConfig::load_from(&source).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert!(matches!(errors[0], EnvManError::Parse { .. }));
+    assert_eq!(
+        errors[0].source().unwrap().to_string(),
+        "unknown value 'yaml', expected one of: json, plain-text, text, human, bunyan"
+    );
+    assert_eq!(
+        errors[1].source().unwrap().to_string(),
+        "unknown value 'trace', expected one of: DEBUG, INFO"
+    );
+}
@@ -1,4 +1,4 @@
-use envman::EnvMan;
+use envman::{env_map, EnvMan, EnvManError, ParseElement};
 
 #[derive(EnvMan, Debug)]
 #[envman(prefix = "ERROR_")]
@@ -9,10 +9,12 @@ pub struct ErrorConfig {
 
 #[test]
 fn parse_error_contains_details() {
-    std::env::set_var("ERROR_PORT", "not_a_number");
-    std::env::set_var("ERROR_COUNT", "100");
+    let source = env_map! {
+        "ERROR_PORT" => "not_a_number",
+        "ERROR_COUNT" => "100",
+    };
 
-    let result = ErrorConfig::load_from_env();
+    let result = ErrorConfig::load_from(&source);
 
     assert!(result.is_err());
 
@@ -20,21 +22,62 @@ fn parse_error_contains_details() {
     let err_string = format!("{}", err);
 
     // Error should match exact format
-    assert_eq!(err_string, "failed to parse environment variable 'ERROR_PORT' with value 'not_a_number' (expected type: error_test::ErrorConfig)");
+    assert_eq!(err_string, "failed to parse environment variable 'ERROR_PORT' with value 'not_a_number' for field `ErrorConfig::port` (expected type: u16)");
+}
+
+#[test]
+fn parse_error_of_separator_element() {
+    #[derive(EnvMan, Debug)]
+    #[envman(prefix = "ELEMENT_")]
+    #[allow(dead_code)]
+    pub struct ElementConfig {
+        #[envman(separator = ",")]
+        pub ports: Option<Vec<u16>>,
+    }
+
+    let source = env_map! { "ELEMENT_PORTS" => "80, http,443" };
 
-    std::env::remove_var("ERROR_PORT");
-    std::env::remove_var("ERROR_COUNT");
+    let err = ElementConfig::load_from(&source).unwrap_err();
+
+    match &err {
+        EnvManError::Parse {
+            key,
+            value,
+            expected_type,
+            field,
+            struct_name,
+            element,
+            ..
+        } => {
+            assert_eq!(*key, "ELEMENT_PORTS");
+            assert_eq!(value, " http");
+            assert_eq!(*expected_type, "alloc::vec::Vec<u16>");
+            assert_eq!(*field, "ports");
+            assert_eq!(*struct_name, "ElementConfig");
+            assert_eq!(
+                element.as_deref(),
+                Some(&ParseElement {
+                    index: 1,
+                    position: None,
+                    expected_type: "u16"
+                })
+            );
+        }
+        err => panic!("expected parse error, got {err:?}"),
+    }
+    assert_eq!(err.to_string(), "failed to parse element 1 of environment variable 'ELEMENT_PORTS' with value ' http' for field `ElementConfig::ports` (expected type: u16, element of alloc::vec::Vec<u16>)");
 }
 
 #[test]
 fn not_found_error_contains_key() {
     #[derive(EnvMan, Debug)]
     #[envman(prefix = "MISSING_")]
+    #[allow(dead_code)]
     pub struct MissingConfig {
         pub required_field: String,
     }
 
-    let result = MissingConfig::load_from_env();
+    let result = MissingConfig::load_from(&env_map! {});
 
     assert!(result.is_err());
 
@@ -0,0 +1,86 @@
+use envman::{env_map, EnvMan, EnvManError, GroupRule};
+
+#[derive(EnvMan, Debug)]
+#[envman(
+    prefix = "AUTH_",
+    group(name = "auth", exactly_one = [api_key, oauth_token, mtls_cert]),
+    group(name = "log", at_most_one = [log_file, syslog])
+)]
+#[allow(dead_code)]
+struct AuthConfig {
+    api_key: Option<String>,
+    oauth_token: Option<String>,
+    #[envman(rename = "MTLS_CERT")]
+    mtls_cert: Option<String>,
+    log_file: Option<String>,
+    #[envman(default = false)]
+    syslog: bool,
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(group(name = "upstream", at_least_one = [primary, fallback]))]
+#[allow(dead_code)]
+struct UpstreamConfig {
+    primary: Option<String>,
+    fallback: Option<String>,
+}
+
+#[test]
+fn exactly_one() {
+    let config = AuthConfig::load_from(&env_map! { "AUTH_OAUTH_TOKEN" => "token" }).unwrap();
+    assert_eq!(config.oauth_token.as_deref(), Some("token"));
+
+    let err = AuthConfig::load_from(&env_map! {}).unwrap_err();
+    assert!(matches!(
+        err,
+        EnvManError::Group {
+            group: "auth",
+            rule: GroupRule::ExactlyOne,
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "exactly one of the environment variables in group 'auth' must be set (AUTH_API_KEY, AUTH_OAUTH_TOKEN, MTLS_CERT), found none"
+    );
+
+    let source = env_map! { "AUTH_API_KEY" => "key", "MTLS_CERT" => "cert.pem" };
+    let err = AuthConfig::load_from(&source).unwrap_err();
+    let EnvManError::Group { set, .. } = &err else {
+        panic!("expected group error, got {err:?}");
+    };
+    assert_eq!(set, &["AUTH_API_KEY", "MTLS_CERT"]);
+}
+
+#[test]
+fn at_most_one_is_reported_with_field_errors() {
+    let source = env_map! {
+        "AUTH_API_KEY" => "key",
+        "AUTH_LOG_FILE" => "/var/log/app.log",
+        "AUTH_SYSLOG" => "maybe",
+    };
+    let err = AuthConfig::load_from(&source).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert!(matches!(errors[0], EnvManError::Parse { .. }));
+    assert!(matches!(
+        errors[1],
+        EnvManError::Group {
+            rule: GroupRule::AtMostOne,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn at_least_one() {
+    let source = env_map! { "PRIMARY" => "a", "FALLBACK" => "b" };
+    assert!(UpstreamConfig::load_from(&source).is_ok());
+
+    let err = UpstreamConfig::load_from(&env_map! {}).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "at least one of the environment variables in group 'upstream' must be set (PRIMARY, FALLBACK), found none"
+    );
+}
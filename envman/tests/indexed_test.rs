@@ -0,0 +1,132 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug, PartialEq)]
+struct ReplicaConfig {
+    url: String,
+    #[envman(default = 5)]
+    pool: u8,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "APP_")]
+struct ClusterConfig {
+    #[envman(nest, indexed)]
+    replicas: Vec<ReplicaConfig>,
+    #[envman(nest, indexed, min = 1, max = 2)]
+    brokers: Option<Vec<ReplicaConfig>>,
+}
+
+#[test]
+fn indexed_nest() {
+    let source = env_map! {
+        "APP_REPLICAS_0_URL" => "mysql://replica-0",
+        "APP_REPLICAS_1_URL" => "mysql://replica-1",
+        "APP_REPLICAS_1_POOL" => "10",
+        "APP_BROKERS_0_URL" => "kafka://broker-0",
+    };
+
+    assert_eq!(
+        ClusterConfig::load_from(&source).unwrap(),
+        ClusterConfig {
+            replicas: vec![
+                ReplicaConfig {
+                    url: String::from("mysql://replica-0"),
+                    pool: 5
+                },
+                ReplicaConfig {
+                    url: String::from("mysql://replica-1"),
+                    pool: 10
+                },
+            ],
+            brokers: Some(vec![ReplicaConfig {
+                url: String::from("kafka://broker-0"),
+                pool: 5
+            }]),
+        }
+    );
+
+    assert_eq!(
+        ClusterConfig::load_from(&env_map! {}).unwrap(),
+        ClusterConfig {
+            replicas: vec![],
+            brokers: None,
+        }
+    );
+}
+
+#[test]
+fn index_gap() {
+    let source = env_map! {
+        "APP_REPLICAS_0_URL" => "mysql://replica-0",
+        "APP_REPLICAS_2_URL" => "mysql://replica-2",
+    };
+
+    let err = ClusterConfig::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::IndexGap { index: 1, .. }));
+    assert_eq!(
+        err.to_string(),
+        "missing index 1 of environment variable 'APP_REPLICAS', indices must start at 0 without gaps"
+    );
+}
+
+#[test]
+fn count_and_element_errors() {
+    let source = env_map! {
+        "APP_REPLICAS_0_URL" => "mysql://replica-0",
+        "APP_REPLICAS_1_POOL" => "many",
+        "APP_BROKERS_0_URL" => "kafka://broker-0",
+        "APP_BROKERS_1_URL" => "kafka://broker-1",
+        "APP_BROKERS_2_URL" => "kafka://broker-2",
+    };
+
+    let err = ClusterConfig::load_from(&source).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert_eq!(errors.len(), 3);
+    assert_eq!(
+        errors[0].to_string(),
+        "replicas.1: failed to read environment variable 'APP_REPLICAS_1_URL'"
+    );
+    assert!(matches!(errors[1], EnvManError::Nested { ref path, .. } if path == "replicas.1"));
+    assert_eq!(errors[1].key(), Some("APP_REPLICAS_1_POOL"));
+    assert_eq!(
+        errors[2].to_string(),
+        "environment variable 'APP_BROKERS' has 3 entries, expected between 1 and 2"
+    );
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "ROOT__", nesting_separator = "__")]
+struct SeparatedConfig {
+    #[envman(nest, indexed)]
+    replicas: Vec<ReplicaConfig>,
+}
+
+#[test]
+fn indexed_with_nesting_separator() {
+    let source = env_map! { "ROOT__REPLICAS__0__URL" => "mysql://replica-0" };
+
+    assert_eq!(
+        SeparatedConfig::load_from(&source).unwrap(),
+        SeparatedConfig {
+            replicas: vec![ReplicaConfig {
+                url: String::from("mysql://replica-0"),
+                pool: 5
+            }],
+        }
+    );
+}
+
+#[test]
+fn skips_indices_with_leading_zeros() {
+    let source = env_map! {
+        "APP_REPLICAS_0_URL" => "mysql://replica-0",
+        "APP_REPLICAS_01_URL" => "mysql://padded",
+        "APP_REPLICAS_00_URL" => "mysql://padded",
+    };
+
+    let config = ClusterConfig::load_from(&source).unwrap();
+    assert_eq!(config.replicas.len(), 1);
+    assert_eq!(config.replicas[0].url, "mysql://replica-0");
+}
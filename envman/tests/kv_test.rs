@@ -0,0 +1,77 @@
+use std::collections::{BTreeMap, HashMap};
+
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct KvConfig {
+    #[envman(separator = ",", kv_separator = "=")]
+    labels: HashMap<String, String>,
+    #[envman(separator = ";", kv_separator = ":", max_items = 3, default = BTreeMap::new())]
+    upstream_weights: BTreeMap<String, u8>,
+    #[envman(separator = ",", kv_separator = "=")]
+    feature_overrides: Option<BTreeMap<String, bool>>,
+}
+
+#[test]
+fn parses_pairs() {
+    let source = env_map! {
+        "LABELS" => "team=core, tier=1,query=a=b",
+        "UPSTREAM_WEIGHTS" => "primary:80;fallback:20",
+    };
+    let config = KvConfig::load_from(&source).unwrap();
+    assert_eq!(
+        config.labels,
+        HashMap::from([
+            (String::from("team"), String::from("core")),
+            (String::from("tier"), String::from("1")),
+            // split at the first separator only
+            (String::from("query"), String::from("a=b")),
+        ])
+    );
+    assert_eq!(config.upstream_weights["primary"], 80);
+    assert_eq!(config.feature_overrides, None);
+}
+
+#[test]
+fn malformed_and_duplicate_pairs() {
+    let source = env_map! {
+        "LABELS" => "team=core,tier",
+        "UPSTREAM_WEIGHTS" => "primary:80;primary:20",
+        "FEATURE_OVERRIDES" => "search=maybe",
+    };
+    let err = KvConfig::load_from(&source).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert!(matches!(
+        errors[0],
+        EnvManError::MalformedPair { index: 1, .. }
+    ));
+    assert_eq!(
+        errors[0].to_string(),
+        "element 1 of environment variable 'LABELS' with value 'tier' is not a pair separated by '='"
+    );
+    assert!(matches!(
+        errors[1],
+        EnvManError::DuplicateKey { index: 1, .. }
+    ));
+    assert_eq!(
+        errors[1].to_string(),
+        "element 1 of environment variable 'UPSTREAM_WEIGHTS' repeats the key 'primary'"
+    );
+    assert_eq!(
+        errors[2].to_string(),
+        "failed to parse position 1 of element 0 of environment variable 'FEATURE_OVERRIDES' with value 'maybe' for field `KvConfig::feature_overrides` (expected type: bool, element of alloc::collections::btree::map::BTreeMap<alloc::string::String, bool>)"
+    );
+}
+
+#[test]
+fn limits_number_of_pairs() {
+    let source = env_map! { "LABELS" => "a=b", "UPSTREAM_WEIGHTS" => "a:1;b:1;c:1;d:1" };
+    let err = KvConfig::load_from(&source).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "environment variable 'UPSTREAM_WEIGHTS' has 4 entries, expected at most 3"
+    );
+}
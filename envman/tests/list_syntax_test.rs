@@ -0,0 +1,102 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug)]
+#[envman(list_syntax = "quoted")]
+#[allow(dead_code)]
+struct QuotedConfig {
+    #[envman(separator = ",")]
+    patterns: Vec<String>,
+    #[envman(separator = ",", default = Vec::new())]
+    ports: Vec<u16>,
+    #[envman(separator = ",", list_syntax = "plain", default = Vec::new())]
+    plain: Vec<String>,
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(list_syntax = "json")]
+#[allow(dead_code)]
+struct JsonConfig {
+    #[envman(separator = ",")]
+    patterns: Vec<String>,
+    #[envman(separator = ",", default = Vec::new())]
+    ports: Vec<u16>,
+}
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct FieldConfig {
+    #[envman(separator = ";", list_syntax = "quoted", tuple_separator = "=")]
+    names: Vec<(String, u8)>,
+}
+
+#[test]
+fn respects_quotes_and_escapes() {
+    let source = env_map! {
+        "PATTERNS" => r#"^a,b$, "x, y" ,, ' keep ',c\,d"#,
+        "PLAIN" => "a, \"b, c\"",
+    };
+    let config = QuotedConfig::load_from(&source).unwrap();
+    assert_eq!(config.patterns, ["^a", "b$", "x, y", "", " keep ", "c,d"]);
+    assert_eq!(config.plain, ["a", "\"b", "c\""]);
+
+    let source = env_map! { "NAMES" => "\"Doe; Jane\"=1; Smith=2" };
+    let config = FieldConfig::load_from(&source).unwrap();
+    assert_eq!(
+        config.names,
+        vec![(String::from("Doe; Jane"), 1), (String::from("Smith"), 2)]
+    );
+}
+
+#[test]
+fn keeps_backslashes_of_patterns() {
+    let source = env_map! { "PATTERNS" => r#""^\d+$", '\w+\.', \s\,\\, "say \"hi\"""# };
+    let config = QuotedConfig::load_from(&source).unwrap();
+    assert_eq!(
+        config.patterns,
+        [r"^\d+$", r"\w+\.", r"\s,\", r#"say "hi""#]
+    );
+}
+
+#[test]
+fn keeps_brackets_of_quoted_elements() {
+    let source = env_map! { "PATTERNS" => "[a-z]+,[0-9]+", "PORTS" => "[80]" };
+    let err = QuotedConfig::load_from(&source).unwrap_err();
+    assert_eq!(err.key(), Some("PORTS"));
+
+    let config = QuotedConfig::load_from(&env_map! { "PATTERNS" => "[a-z]+,[0-9]+" }).unwrap();
+    assert_eq!(config.patterns, ["[a-z]+", "[0-9]+"]);
+}
+
+#[test]
+fn accepts_json_arrays() {
+    let source = env_map! {
+        "PATTERNS" => r#"["a,b", "say \"hi\"", ""]"#,
+        "PORTS" => "[80, 443]",
+    };
+    let config = JsonConfig::load_from(&source).unwrap();
+    assert_eq!(config.patterns, ["a,b", "say \"hi\"", ""]);
+    assert_eq!(config.ports, [80, 443]);
+
+    let config = JsonConfig::load_from(&env_map! { "PATTERNS" => "[]", "PORTS" => "" }).unwrap();
+    assert!(config.patterns.is_empty());
+    assert!(config.ports.is_empty());
+
+    // Values that are not JSON arrays use the quoted syntax
+    let config = JsonConfig::load_from(&env_map! { "PATTERNS" => "a, \"b, c\"" }).unwrap();
+    assert_eq!(config.patterns, ["a", "b, c"]);
+}
+
+#[test]
+fn reports_invalid_lists() {
+    let err = QuotedConfig::load_from(&env_map! { "PATTERNS" => "a, \"b" }).unwrap_err();
+    assert!(matches!(err, EnvManError::ListSyntax { .. }));
+    assert_eq!(
+        err.to_string(),
+        "environment variable 'PATTERNS' with value 'a, \"b' is not a valid list: unterminated \" starting at byte 3"
+    );
+
+    let err = JsonConfig::load_from(&env_map! { "PATTERNS" => "[\"a\", [1]]" }).unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("JSON array items must be strings, numbers, booleans or null"));
+}
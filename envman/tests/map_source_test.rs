@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use envman::{env_map, EnvMan, MapSource};
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "MAP_")]
+struct MapConfig {
+    host: String,
+    #[envman(default = 80)]
+    port: u16,
+}
+
+#[test]
+fn from_hash_map() {
+    let vars = HashMap::from([(String::from("MAP_HOST"), String::from("localhost"))]);
+
+    assert_eq!(
+        MapConfig::load_from(&MapSource::from(vars)).unwrap(),
+        MapConfig {
+            host: String::from("localhost"),
+            port: 80
+        }
+    );
+}
+
+#[test]
+fn from_iterator() {
+    let source: MapSource = [("MAP_HOST", "localhost"), ("MAP_PORT", "8080")]
+        .into_iter()
+        .collect();
+
+    assert_eq!(
+        MapConfig::load_from(&source).unwrap(),
+        MapConfig {
+            host: String::from("localhost"),
+            port: 8080
+        }
+    );
+}
+
+#[test]
+fn from_macro() {
+    let mut source = env_map! {
+        "MAP_HOST" => "localhost",
+        "MAP_PORT" => String::from("8080"),
+    };
+    assert_eq!(MapConfig::load_from(&source).unwrap().port, 8080);
+
+    source.remove("MAP_PORT");
+    assert_eq!(MapConfig::load_from(&source).unwrap().port, 80);
+
+    source.remove("MAP_HOST");
+    assert!(MapConfig::load_from(&source).is_err());
+}
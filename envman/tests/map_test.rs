@@ -0,0 +1,103 @@
+use std::collections::{BTreeMap, HashMap};
+
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug, PartialEq)]
+struct TenantConfig {
+    url: String,
+    #[envman(default = 5)]
+    max_conn: u8,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "APP_")]
+struct MultiTenantConfig {
+    #[envman(nest, map)]
+    tenants: HashMap<String, TenantConfig>,
+}
+
+#[test]
+fn map_nest() {
+    let source = env_map! {
+        "APP_TENANTS_ACME_CORP_URL" => "mysql://acme",
+        "APP_TENANTS_ACME_CORP_MAX_CONN" => "10",
+        "APP_TENANTS_GLOBEX_URL" => "mysql://globex",
+        "APP_TENANTS_GLOBEX_UNKNOWN" => "ignored",
+        "APP_OTHER_URL" => "ignored",
+    };
+
+    let config = MultiTenantConfig::load_from(&source).unwrap();
+    assert_eq!(
+        config.tenants,
+        HashMap::from([
+            (
+                String::from("acme_corp"),
+                TenantConfig {
+                    url: String::from("mysql://acme"),
+                    max_conn: 10
+                }
+            ),
+            (
+                String::from("globex"),
+                TenantConfig {
+                    url: String::from("mysql://globex"),
+                    max_conn: 5
+                }
+            ),
+        ])
+    );
+}
+
+#[test]
+fn map_entry_error() {
+    let source = env_map! { "APP_TENANTS_ACME_MAX_CONN" => "10" };
+
+    let err = MultiTenantConfig::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::Nested { ref path, .. } if path == "tenants.acme"));
+    assert_eq!(err.key(), Some("APP_TENANTS_ACME_URL"));
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(rename_all = "PascalCase")]
+struct PascalTenant {
+    api_key: String,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+struct PascalConfig {
+    #[envman(nest, map, min = 1)]
+    tenants: Option<BTreeMap<String, PascalTenant>>,
+}
+
+#[test]
+fn map_key_follows_rename_all() {
+    let source = env_map! {
+        "TENANTS_AcmeCorp_ApiKey" => "key-1",
+        "TENANTS_Globex_ApiKey" => "key-2",
+    };
+
+    assert_eq!(
+        PascalConfig::load_from(&source).unwrap(),
+        PascalConfig {
+            tenants: Some(BTreeMap::from([
+                (
+                    String::from("acme_corp"),
+                    PascalTenant {
+                        api_key: String::from("key-1")
+                    }
+                ),
+                (
+                    String::from("globex"),
+                    PascalTenant {
+                        api_key: String::from("key-2")
+                    }
+                ),
+            ]))
+        }
+    );
+
+    assert_eq!(
+        PascalConfig::load_from(&env_map! {}).unwrap(),
+        PascalConfig { tenants: None }
+    );
+}
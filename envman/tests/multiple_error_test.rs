@@ -0,0 +1,76 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+fn validate_positive(value: &i32) -> Result<(), String> {
+    if *value > 0 {
+        Ok(())
+    } else {
+        Err(String::from("must be positive"))
+    }
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "MULTI_")]
+#[allow(dead_code)]
+struct MultiConfig {
+    name: String,
+    port: u16,
+    #[envman(validate = validate_positive)]
+    workers: i32,
+    #[envman(nest)]
+    db: MultiDbConfig,
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "MULTI_DB_")]
+#[allow(dead_code)]
+struct MultiDbConfig {
+    url: String,
+    pool: u8,
+}
+
+#[test]
+fn collects_every_field_error() {
+    let source = env_map! {
+        "MULTI_PORT" => "http",
+        "MULTI_WORKERS" => "0",
+        "MULTI_DB_POOL" => "many",
+    };
+
+    let err = MultiConfig::load_from(&source).unwrap_err();
+
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert_eq!(errors.len(), 5);
+    assert!(matches!(errors[0], EnvManError::NotFound { .. }));
+    assert_eq!(errors[0].key(), Some("MULTI_NAME"));
+    assert!(matches!(errors[1], EnvManError::Parse { .. }));
+    assert_eq!(errors[1].key(), Some("MULTI_PORT"));
+    assert!(matches!(errors[2], EnvManError::Validation { .. }));
+    assert_eq!(errors[2].key(), Some("MULTI_WORKERS"));
+    // errors of the nested struct are flattened
+    assert!(matches!(errors[3], EnvManError::Nested { ref path, .. } if path == "db"));
+    assert_eq!(errors[3].key(), Some("MULTI_DB_URL"));
+    assert!(matches!(errors[4], EnvManError::Nested { ref path, .. } if path == "db"));
+    assert_eq!(errors[4].key(), Some("MULTI_DB_POOL"));
+
+    assert!(err.to_string().starts_with(
+        "multiple errors occurred while loading environment variables:\n  1. failed to read environment variable 'MULTI_NAME'\n  2. "
+    ));
+}
+
+#[test]
+fn single_error_is_not_wrapped() {
+    let source = env_map! {
+        "MULTI_NAME" => "app",
+        "MULTI_PORT" => "8080",
+        "MULTI_WORKERS" => "4",
+        "MULTI_DB_POOL" => "5",
+    };
+
+    let err = MultiConfig::load_from(&source).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "db: failed to read environment variable 'MULTI_DB_URL'"
+    );
+}
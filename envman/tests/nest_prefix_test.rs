@@ -0,0 +1,110 @@
+use envman::{env_map, EnvMan};
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "DB_")]
+struct DbConfig {
+    url: String,
+    #[envman(default = 5)]
+    pool: u8,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+struct Databases {
+    #[envman(nest, prefix = "PRIMARY_DB_")]
+    primary: DbConfig,
+    #[envman(nest, prefix = "REPLICA_DB_")]
+    replica: Option<DbConfig>,
+    #[envman(nest)]
+    fallback: DbConfig,
+}
+
+#[test]
+fn prefix_override() {
+    let source = env_map! {
+        "PRIMARY_DB_URL" => "mysql://primary",
+        "PRIMARY_DB_POOL" => "10",
+        "REPLICA_DB_URL" => "mysql://replica",
+        "DB_URL" => "mysql://fallback",
+    };
+
+    assert_eq!(
+        Databases::load_from(&source).unwrap(),
+        Databases {
+            primary: DbConfig {
+                url: String::from("mysql://primary"),
+                pool: 10
+            },
+            replica: Some(DbConfig {
+                url: String::from("mysql://replica"),
+                pool: 5
+            }),
+            fallback: DbConfig {
+                url: String::from("mysql://fallback"),
+                pool: 5
+            },
+        }
+    );
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "APP_")]
+struct AppConfig {
+    name: String,
+    #[envman(nest)]
+    db: InheritDbConfig,
+    #[envman(nest, prefix = "CACHE_")]
+    cache: InheritDbConfig,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "DB_", inherit_prefix)]
+struct InheritDbConfig {
+    url: String,
+    #[envman(nest)]
+    pool: PoolConfig,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "POOL_", inherit_prefix)]
+struct PoolConfig {
+    size: u8,
+}
+
+#[test]
+fn inherit_prefix() {
+    let source = env_map! {
+        "APP_NAME" => "app",
+        "APP_DB_URL" => "mysql://db",
+        "APP_DB_POOL_SIZE" => "4",
+        "APP_CACHE_URL" => "redis://cache",
+        "APP_CACHE_POOL_SIZE" => "8",
+    };
+
+    assert_eq!(
+        AppConfig::load_from(&source).unwrap(),
+        AppConfig {
+            name: String::from("app"),
+            db: InheritDbConfig {
+                url: String::from("mysql://db"),
+                pool: PoolConfig { size: 4 }
+            },
+            cache: InheritDbConfig {
+                url: String::from("redis://cache"),
+                pool: PoolConfig { size: 8 }
+            },
+        }
+    );
+
+    // Loaded on its own, the struct only uses its own prefix
+    let source = env_map! {
+        "DB_URL" => "mysql://db",
+        "DB_POOL_SIZE" => "4",
+    };
+    assert_eq!(
+        InheritDbConfig::load_from(&source).unwrap(),
+        InheritDbConfig {
+            url: String::from("mysql://db"),
+            pool: PoolConfig { size: 4 }
+        }
+    );
+}
@@ -3,7 +3,7 @@ use std::{
     str::FromStr,
 };
 
-use envman::EnvMan;
+use envman::{env_map, EnvMan, EnvManError};
 
 #[derive(EnvMan, Debug, PartialEq)]
 struct TestNest {
@@ -29,10 +29,9 @@ impl Default for BackEndConfig {
 
 #[test]
 fn nest() {
-    std::env::set_var("SERVER_IP", "127.0.0.1:80");
-    std::env::set_var("BACKEND_IP", "127.0.0.1:5000");
+    let mut source = env_map! { "SERVER_IP" => "127.0.0.1:80" };
 
-    // Not Set 'BACKEND_KIND', backend is Default
+    // No 'BACKEND_*' is set, backend is Default
     assert_eq!(
         TestNest {
             server_ip: SocketAddr::from_str("127.0.0.1:80").unwrap(),
@@ -41,10 +40,11 @@ fn nest() {
                 backend_kind: 0
             })
         },
-        TestNest::load_from_env().unwrap()
+        TestNest::load_from(&source).unwrap()
     );
 
-    std::env::set_var("BACKEND_KIND", "5");
+    source.insert("BACKEND_IP", "127.0.0.1:5000");
+    source.insert("BACKEND_KIND", "5");
 
     assert_eq!(
         TestNest {
@@ -54,6 +54,66 @@ fn nest() {
                 backend_kind: 5
             })
         },
-        TestNest::load_from_env().unwrap()
+        TestNest::load_from(&source).unwrap()
     );
 }
+
+#[test]
+fn partially_set_nest_is_an_error() {
+    // 'BACKEND_KIND' is not set, but 'BACKEND_IP' is, so the default is not used
+    let source = env_map! {
+        "SERVER_IP" => "127.0.0.1:80",
+        "BACKEND_IP" => "127.0.0.1:5000",
+    };
+
+    let err = TestNest::load_from(&source).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "backend: failed to read environment variable 'BACKEND_KIND'"
+    );
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "OUTER_")]
+struct OptionalNest {
+    #[envman(nest)]
+    inner: Option<InnerConfig>,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "INNER_")]
+struct InnerConfig {
+    #[envman(default = 1)]
+    level: u8,
+    #[envman(nest)]
+    deep: Option<DeepConfig>,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "DEEP_")]
+struct DeepConfig {
+    value: u8,
+}
+
+#[test]
+fn optional_nest() {
+    assert_eq!(
+        OptionalNest::load_from(&env_map! {}).unwrap(),
+        OptionalNest { inner: None }
+    );
+
+    // A variable of a nested struct makes every struct on the way present
+    assert_eq!(
+        OptionalNest::load_from(&env_map! { "DEEP_VALUE" => "3" }).unwrap(),
+        OptionalNest {
+            inner: Some(InnerConfig {
+                level: 1,
+                deep: Some(DeepConfig { value: 3 })
+            })
+        }
+    );
+
+    let err = OptionalNest::load_from(&env_map! { "DEEP_VALUE" => "x" }).unwrap_err();
+    assert!(matches!(err, EnvManError::Nested { ref path, .. } if path == "inner.deep"));
+    assert_eq!(err.key(), Some("DEEP_VALUE"));
+}
@@ -0,0 +1,62 @@
+use envman::{env_map, EnvMan};
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "APP__", nesting_separator = "__")]
+struct AppConfig {
+    name: String,
+    #[envman(nest)]
+    database: DatabaseConfig,
+    #[envman(nest, prefix = "CACHE__")]
+    redis: Option<PoolConfig>,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "DB_")]
+struct DatabaseConfig {
+    url: String,
+    #[envman(nest)]
+    pool: PoolConfig,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+struct PoolConfig {
+    max_size: u32,
+}
+
+#[test]
+fn keys_follow_the_struct_tree() {
+    let source = env_map! {
+        "APP__NAME" => "app",
+        "APP__DATABASE__URL" => "mysql://example",
+        "APP__DATABASE__POOL__MAX_SIZE" => "16",
+        "APP__CACHE__MAX_SIZE" => "4",
+    };
+
+    assert_eq!(
+        AppConfig::load_from(&source).unwrap(),
+        AppConfig {
+            name: String::from("app"),
+            database: DatabaseConfig {
+                url: String::from("mysql://example"),
+                pool: PoolConfig { max_size: 16 }
+            },
+            redis: Some(PoolConfig { max_size: 4 }),
+        }
+    );
+}
+
+#[test]
+fn own_prefix_is_used_at_the_root() {
+    let source = env_map! {
+        "DB_URL" => "mysql://example",
+        "MAX_SIZE" => "8",
+    };
+
+    assert_eq!(
+        DatabaseConfig::load_from(&source).unwrap(),
+        DatabaseConfig {
+            url: String::from("mysql://example"),
+            pool: PoolConfig { max_size: 8 }
+        }
+    );
+}
@@ -1,6 +1,6 @@
 use std::{net::SocketAddr, str::FromStr};
 
-use envman::EnvMan;
+use envman::{env_map, EnvMan};
 
 #[derive(EnvMan, Debug, PartialEq)]
 struct TestNormal {
@@ -12,12 +12,14 @@ struct TestNormal {
 
 #[test]
 fn normal() {
-    std::env::set_var("DB_URL", "mysql://example");
-    std::env::set_var("DB_IP", "127.0.0.1:80");
-    std::env::set_var("DB_MAX_CONN", "5");
+    let source = env_map! {
+        "DB_URL" => "mysql://example",
+        "DB_IP" => "127.0.0.1:80",
+        "DB_MAX_CONN" => "5",
+    };
 
     assert_eq!(
-        TestNormal::load_from_env().unwrap(),
+        TestNormal::load_from(&source).unwrap(),
         TestNormal {
             db_url: String::from("mysql://example"),
             db_ip: SocketAddr::from_str("127.0.0.1:80").unwrap(),
@@ -1,4 +1,4 @@
-use envman::EnvMan;
+use envman::{env_map, EnvMan};
 
 #[derive(EnvMan, Debug, PartialEq)]
 struct TestOption {
@@ -12,7 +12,7 @@ struct TestOption {
 #[test]
 fn option() {
     assert_eq!(
-        TestOption::load_from_env().unwrap(),
+        TestOption::load_from(&env_map! {}).unwrap(),
         TestOption {
             secret_1: None,
             secret_2: Some(String::from("5"))
@@ -0,0 +1,67 @@
+#![cfg(unix)]
+
+use std::{ffi::OsString, os::unix::ffi::OsStringExt, path::PathBuf};
+
+use envman::{env_map, EnvMan, EnvManError, MapSource};
+
+fn non_unicode() -> OsString {
+    OsString::from_vec(vec![b'/', b'd', b'a', b't', b'a', 0xff])
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "UNICODE_")]
+struct UnicodeConfig {
+    #[envman(default = "fallback")]
+    name: String,
+    label: Option<String>,
+}
+
+#[test]
+fn not_unicode_is_not_treated_as_missing() {
+    let mut source = MapSource::new();
+    source.insert("UNICODE_NAME", non_unicode());
+    source.insert("UNICODE_LABEL", non_unicode());
+
+    let err = UnicodeConfig::load_from(&source).unwrap_err();
+
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert!(matches!(errors[0], EnvManError::NotUnicode { .. }));
+    assert_eq!(errors[0].key(), Some("UNICODE_NAME"));
+    assert!(matches!(errors[1], EnvManError::NotUnicode { .. }));
+    assert_eq!(errors[1].key(), Some("UNICODE_LABEL"));
+    assert_eq!(
+        errors[0].to_string(),
+        "environment variable 'UNICODE_NAME' is not valid unicode"
+    );
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "OS_")]
+struct OsConfig {
+    #[envman(os)]
+    data_dir: PathBuf,
+    #[envman(os)]
+    raw: OsString,
+    #[envman(os)]
+    cache_dir: Option<PathBuf>,
+    #[envman(os, default = "/tmp")]
+    tmp_dir: PathBuf,
+}
+
+#[test]
+fn os_fields_are_byte_exact() {
+    let mut source = env_map! { "OS_RAW" => "raw" };
+    source.insert("OS_DATA_DIR", non_unicode());
+
+    assert_eq!(
+        OsConfig::load_from(&source).unwrap(),
+        OsConfig {
+            data_dir: PathBuf::from(non_unicode()),
+            raw: OsString::from("raw"),
+            cache_dir: None,
+            tmp_dir: PathBuf::from("/tmp"),
+        }
+    );
+}
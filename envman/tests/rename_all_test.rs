@@ -1,4 +1,4 @@
-use envman::EnvMan;
+use envman::{env_map, EnvMan};
 
 #[derive(EnvMan, Debug, PartialEq)]
 #[envman(rename_all = "kebab-case", prefix = "db-", suffix = "-main")]
@@ -8,9 +8,9 @@ struct TestRenameAll {
 
 #[test]
 fn rename_all() {
-    std::env::set_var("db-url-main", "mysql://example.1");
+    let source = env_map! { "db-url-main" => "mysql://example.1" };
     assert_eq!(
-        TestRenameAll::load_from_env().unwrap(),
+        TestRenameAll::load_from(&source).unwrap(),
         TestRenameAll {
             url: String::from("mysql://example.1"),
         }
@@ -1,4 +1,4 @@
-use envman::EnvMan;
+use envman::{env_map, EnvMan};
 
 #[derive(EnvMan, Debug, PartialEq)]
 struct TestRename {
@@ -13,11 +13,13 @@ struct TestRename {
 
 #[test]
 fn rename() {
-    std::env::set_var("CORE_DB_URL", "mysql://example.1");
-    std::env::set_var("transaction_db_url", "mysql://example.2");
+    let source = env_map! {
+        "CORE_DB_URL" => "mysql://example.1",
+        "transaction_db_url" => "mysql://example.2",
+    };
 
     assert_eq!(
-        TestRename::load_from_env().unwrap(),
+        TestRename::load_from(&source).unwrap(),
         TestRename {
             db_url_1: String::from("mysql://example.1"),
             db_url_2: String::from("mysql://example.2")
@@ -0,0 +1,109 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "APP_")]
+#[allow(dead_code)]
+struct ServerConfig {
+    #[envman(default = false)]
+    tls_enabled: bool,
+    #[envman(required_if(TLS_ENABLED = "true"))]
+    tls_cert: Option<String>,
+    #[envman(required_unless = "DATABASE_HOST")]
+    database_url: Option<String>,
+    #[envman(required_unless = "DATABASE_URL")]
+    database_host: Option<String>,
+    #[envman(conflicts_with = "API_KEY_FILE")]
+    api_key: Option<String>,
+    api_key_file: Option<String>,
+}
+
+#[test]
+fn either_field_satisfies_requirement() {
+    let config =
+        ServerConfig::load_from(&env_map! { "APP_DATABASE_URL" => "postgres://db" }).unwrap();
+    assert_eq!(config.database_url.as_deref(), Some("postgres://db"));
+
+    let config = ServerConfig::load_from(&env_map! { "APP_DATABASE_HOST" => "db" }).unwrap();
+    assert_eq!(config.database_host.as_deref(), Some("db"));
+
+    let err = ServerConfig::load_from(&env_map! {}).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert_eq!(
+        errors[0].to_string(),
+        "environment variable 'APP_DATABASE_URL' is required unless 'APP_DATABASE_HOST' is set"
+    );
+    assert_eq!(errors[1].key(), Some("APP_DATABASE_HOST"));
+}
+
+#[test]
+fn required_if_value_matches() {
+    let source = env_map! { "APP_TLS_ENABLED" => "true", "APP_DATABASE_HOST" => "db" };
+    let err = ServerConfig::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::Required { .. }));
+    assert_eq!(
+        err.to_string(),
+        "environment variable 'APP_TLS_CERT' is required when 'APP_TLS_ENABLED' is 'true'"
+    );
+
+    let source = env_map! { "APP_TLS_ENABLED" => "false", "APP_DATABASE_HOST" => "db" };
+    assert_eq!(ServerConfig::load_from(&source).unwrap().tls_cert, None);
+}
+
+#[test]
+fn conflicting_fields() {
+    let source = env_map! {
+        "APP_DATABASE_HOST" => "db",
+        "APP_API_KEY" => "key",
+        "APP_API_KEY_FILE" => "/run/secrets/key",
+    };
+    let err = ServerConfig::load_from(&source).unwrap_err();
+    assert!(matches!(
+        err,
+        EnvManError::Conflict { ref key, ref other } if key == "APP_API_KEY" && other == "APP_API_KEY_FILE"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "environment variables 'APP_API_KEY' and 'APP_API_KEY_FILE' cannot both be set"
+    );
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "APP_", suffix = "_V1")]
+#[allow(dead_code)]
+struct VersionedConfig {
+    #[envman(default = false)]
+    tls_enabled: bool,
+    #[envman(required_if(TLS_ENABLED = "true"), conflicts_with = "LEGACY")]
+    tls_cert: Option<String>,
+    #[envman(rename = "OLD_CERT")]
+    legacy: Option<String>,
+    #[envman(required_unless = "EXTERNAL_CA")]
+    ca: Option<String>,
+}
+
+#[test]
+fn rules_use_the_keys_of_named_fields() {
+    let err = VersionedConfig::load_from(&env_map! { "APP_TLS_ENABLED_V1" => "true" }).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert_eq!(
+        errors[0].to_string(),
+        "environment variable 'APP_TLS_CERT_V1' is required when 'APP_TLS_ENABLED_V1' is 'true'"
+    );
+    // Variables that are not fields get the prefix and suffix
+    assert_eq!(
+        errors[1].to_string(),
+        "environment variable 'APP_CA_V1' is required unless 'APP_EXTERNAL_CA_V1' is set"
+    );
+
+    let source = env_map! {
+        "APP_TLS_CERT_V1" => "cert",
+        "OLD_CERT" => "old",
+        "APP_EXTERNAL_CA_V1" => "1",
+    };
+    let err = VersionedConfig::load_from(&source).unwrap_err();
+    assert!(matches!(err, EnvManError::Conflict { ref other, .. } if other == "OLD_CERT"));
+}
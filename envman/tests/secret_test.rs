@@ -1,4 +1,4 @@
-use envman::{EnvMan, EnvManDebug};
+use envman::{env_map, EnvMan, EnvManDebug};
 
 #[derive(EnvMan, EnvManDebug)]
 #[envman(prefix = "SECRET_")]
@@ -16,12 +16,14 @@ pub struct SecretConfig {
 
 #[test]
 fn secret_masking_debug() {
-    std::env::set_var("SECRET_USERNAME", "admin");
-    std::env::set_var("SECRET_PASSWORD", "super_secret_pass");
-    std::env::set_var("SECRET_API_KEY", "sk-1234567890abcdef");
-    std::env::set_var("SECRET_HOST", "localhost");
+    let source = env_map! {
+        "SECRET_USERNAME" => "admin",
+        "SECRET_PASSWORD" => "super_secret_pass",
+        "SECRET_API_KEY" => "sk-1234567890abcdef",
+        "SECRET_HOST" => "localhost",
+    };
 
-    let config = SecretConfig::load_from_env().unwrap();
+    let config = SecretConfig::load_from(&source).unwrap();
 
     let debug_output = format!("{:?}", config);
 
@@ -30,11 +32,6 @@ fn secret_masking_debug() {
         debug_output,
         "SecretConfig { username: \"admin\", password: \"***\", api_key: \"***\", host: \"localhost\" }"
     );
-
-    std::env::remove_var("SECRET_USERNAME");
-    std::env::remove_var("SECRET_PASSWORD");
-    std::env::remove_var("SECRET_API_KEY");
-    std::env::remove_var("SECRET_HOST");
 }
 
 #[derive(EnvMan, EnvManDebug)]
@@ -47,17 +44,15 @@ pub struct OptionalSecretConfig {
 #[test]
 fn optional_secret_masking() {
     // Test with value
-    std::env::set_var("OPTIONAL_SECRET_TOKEN", "secret_token_123");
-    let config = OptionalSecretConfig::load_from_env().unwrap();
+    let source = env_map! { "OPTIONAL_SECRET_TOKEN" => "secret_token_123" };
+    let config = OptionalSecretConfig::load_from(&source).unwrap();
     let debug_output = format!("{:?}", config);
 
     // Verify exact format with Some value masked
     assert_eq!(debug_output, "OptionalSecretConfig { token: \"***\" }");
 
-    std::env::remove_var("OPTIONAL_SECRET_TOKEN");
-
     // Test without value
-    let config = OptionalSecretConfig::load_from_env().unwrap();
+    let config = OptionalSecretConfig::load_from(&env_map! {}).unwrap();
     let debug_output = format!("{:?}", config);
 
     // Verify exact format with None value masked
@@ -0,0 +1,64 @@
+use std::{collections::HashMap, ffi::OsString};
+
+use envman::{EnvMan, EnvSource};
+
+struct Vars(HashMap<&'static str, &'static str>);
+
+impl EnvSource for Vars {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        self.0.get(key).map(OsString::from)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.0.keys().map(|k| k.to_string()).collect()
+    }
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "SOURCE_")]
+struct SourceConfig {
+    name: String,
+    #[envman(default = 8080)]
+    port: u16,
+    #[envman(nest)]
+    db: SourceDbConfig,
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(prefix = "SOURCE_DB_")]
+struct SourceDbConfig {
+    url: String,
+}
+
+#[test]
+fn load_from_custom_source() {
+    let vars = Vars(HashMap::from([
+        ("SOURCE_NAME", "app"),
+        ("SOURCE_DB_URL", "mysql://example"),
+    ]));
+
+    assert_eq!(
+        SourceConfig::load_from(&vars).unwrap(),
+        SourceConfig {
+            name: String::from("app"),
+            port: 8080,
+            db: SourceDbConfig {
+                url: String::from("mysql://example")
+            }
+        }
+    );
+}
+
+#[test]
+fn load_from_does_not_read_process_env() {
+    std::env::set_var("SOURCE_DB_URL", "mysql://process");
+
+    let vars = Vars(HashMap::from([("SOURCE_NAME", "app")]));
+    let err = SourceConfig::load_from(&vars).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "db: failed to read environment variable 'SOURCE_DB_URL'"
+    );
+
+    std::env::remove_var("SOURCE_DB_URL");
+}
@@ -1,4 +1,4 @@
-use envman::EnvMan;
+use envman::{env_map, EnvMan};
 
 #[derive(EnvMan, Debug, PartialEq)]
 struct TestTestValue {
@@ -13,7 +13,7 @@ struct TestTestValue {
 #[test]
 fn test_value() {
     assert_eq!(
-        TestTestValue::load_from_env().unwrap(),
+        TestTestValue::load_from(&env_map! {}).unwrap(),
         TestTestValue {
             secret_1: String::from("2"),
             secret_2: String::from("3")
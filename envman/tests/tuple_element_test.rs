@@ -0,0 +1,76 @@
+use std::collections::BTreeSet;
+
+use envman::{env_map, EnvMan, EnvManError, ParseElement};
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct ElementConfig {
+    #[envman(separator = ",", tuple_separator = ":")]
+    hosts: Vec<(String, u16)>,
+    #[envman(separator = ";", inner_separator = ",", default = Vec::new())]
+    shards: Vec<Vec<u8>>,
+    #[envman(separator = ";", inner_separator = "|", default = Vec::new())]
+    zones: Vec<BTreeSet<String>>,
+}
+
+#[test]
+fn parses_tuples_and_nested_lists() {
+    let source = env_map! {
+        "HOSTS" => "a:80, b:443",
+        "SHARDS" => "1,2;3",
+        "ZONES" => "b|a;c",
+    };
+    let config = ElementConfig::load_from(&source).unwrap();
+    assert_eq!(
+        config.hosts,
+        vec![(String::from("a"), 80), (String::from("b"), 443)]
+    );
+    assert_eq!(config.shards, vec![vec![1, 2], vec![3]]);
+    assert_eq!(config.zones[0].iter().collect::<Vec<_>>(), ["a", "b"]);
+}
+
+#[test]
+fn reports_failing_position() {
+    let source = env_map! { "HOSTS" => "a:80,b:https" };
+    let err = ElementConfig::load_from(&source).unwrap_err();
+    assert!(matches!(
+        err,
+        EnvManError::Parse {
+            element: Some(ref element),
+            ..
+        } if **element == ParseElement {
+            index: 1,
+            position: Some(1),
+            expected_type: "u16",
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "failed to parse position 1 of element 1 of environment variable 'HOSTS' with value 'https' for field `ElementConfig::hosts` (expected type: u16, element of alloc::vec::Vec<(alloc::string::String, u16)>)"
+    );
+
+    let source = env_map! { "HOSTS" => "a:80", "SHARDS" => "1;2,x" };
+    let err = ElementConfig::load_from(&source).unwrap_err();
+    assert!(err.to_string().starts_with(
+        "failed to parse position 1 of element 1 of environment variable 'SHARDS' with value 'x'"
+    ));
+}
+
+#[test]
+fn reports_wrong_number_of_parts() {
+    let source = env_map! { "HOSTS" => "a:80,b" };
+    let err = ElementConfig::load_from(&source).unwrap_err();
+    assert!(matches!(
+        err,
+        EnvManError::TupleArity {
+            index: 1,
+            found: 1,
+            expected: 2,
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "element 1 of environment variable 'HOSTS' with value 'b' has 1 parts separated by ':', expected 2"
+    );
+}
@@ -0,0 +1,79 @@
+use envman::{env_map, EnvMan, EnvManDebug, EnvManError};
+
+fn validate_port(value: &u16) -> Result<(), String> {
+    if *value >= 1024 {
+        Ok(())
+    } else {
+        Err(String::from("must not be a privileged port"))
+    }
+}
+
+#[derive(EnvMan, Debug, PartialEq)]
+#[envman(key = "PORT")]
+struct HttpPort(#[envman(default = 8080, validate = validate_port)] u16);
+
+#[derive(EnvMan, EnvManDebug)]
+#[envman(key = "DATABASE_URL")]
+struct DatabaseUrl(#[envman(secret)] String);
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "SERVER_")]
+struct Endpoint(#[envman(key = "HOST")] String, #[envman(key = "PORT")] u16);
+
+#[derive(EnvMan, Debug)]
+struct AppConfig {
+    #[envman(nest)]
+    port: HttpPort,
+    #[envman(nest)]
+    database_url: DatabaseUrl,
+}
+
+#[test]
+fn loads_newtype() {
+    assert_eq!(
+        HttpPort::load_from(&env_map! { "PORT" => "3000" }).unwrap(),
+        HttpPort(3000)
+    );
+    assert_eq!(HttpPort::load_from(&env_map! {}).unwrap(), HttpPort(8080));
+
+    let err = HttpPort::load_from(&env_map! { "PORT" => "80" }).unwrap_err();
+    assert!(matches!(err, EnvManError::Validation { .. }));
+    assert_eq!(err.key(), Some("PORT"));
+
+    let err = HttpPort::load_from(&env_map! { "PORT" => "http" }).unwrap_err();
+    assert!(matches!(
+        err,
+        EnvManError::Parse {
+            field: "0",
+            struct_name: "HttpPort",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn loads_tuple_struct_with_prefix() {
+    let source = env_map! { "SERVER_HOST" => "localhost", "SERVER_PORT" => "443" };
+    let endpoint = Endpoint::load_from(&source).unwrap();
+    assert_eq!(endpoint.0, "localhost");
+    assert_eq!(endpoint.1, 443);
+
+    let err = Endpoint::load_from(&env_map! {}).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert_eq!(errors[0].key(), Some("SERVER_HOST"));
+    assert_eq!(errors[1].key(), Some("SERVER_PORT"));
+}
+
+#[test]
+fn newtypes_as_nest_fields() {
+    let source = env_map! { "DATABASE_URL" => "postgres://user:pass@db/app" };
+    let config = AppConfig::load_from(&source).unwrap();
+    assert_eq!(config.port, HttpPort(8080));
+    assert_eq!(config.database_url.0, "postgres://user:pass@db/app");
+    assert_eq!(
+        format!("{:?}", config),
+        "AppConfig { port: HttpPort(8080), database_url: DatabaseUrl(\"***\") }"
+    );
+}
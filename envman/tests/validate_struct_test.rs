@@ -0,0 +1,95 @@
+use envman::{env_map, EnvMan, EnvManError, StructValidationError};
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "POOL_", validate_struct = check_pool)]
+#[allow(dead_code)]
+struct PoolConfig {
+    min_size: u32,
+    max_size: u32,
+}
+
+fn check_pool(config: &PoolConfig) -> Result<(), StructValidationError> {
+    if config.min_size <= config.max_size {
+        Ok(())
+    } else {
+        Err(
+            StructValidationError::new("min_size must not exceed max_size")
+                .field("min_size")
+                .field("max_size"),
+        )
+    }
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(validate_struct = check_tls)]
+#[allow(dead_code)]
+struct TlsConfig {
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+}
+
+fn check_tls(config: &TlsConfig) -> Result<(), &'static str> {
+    if config.tls_cert.is_some() == config.tls_key.is_some() {
+        Ok(())
+    } else {
+        Err("TLS cert and key must both be set")
+    }
+}
+
+#[derive(EnvMan, Debug)]
+#[allow(dead_code)]
+struct AppConfig {
+    #[envman(nest)]
+    pool: PoolConfig,
+    #[envman(nest)]
+    tls: TlsConfig,
+}
+
+#[test]
+fn reports_keys_of_named_fields() {
+    let source = env_map! { "POOL_MIN_SIZE" => "10", "POOL_MAX_SIZE" => "5" };
+    let err = PoolConfig::load_from(&source).unwrap_err();
+    let EnvManError::StructValidation { keys, .. } = &err else {
+        panic!("expected struct validation error, got {err:?}");
+    };
+    assert_eq!(keys, &["POOL_MIN_SIZE", "POOL_MAX_SIZE"]);
+    assert_eq!(
+        err.to_string(),
+        "validation failed for `PoolConfig` (POOL_MIN_SIZE, POOL_MAX_SIZE): min_size must not exceed max_size"
+    );
+
+    let source = env_map! { "POOL_MIN_SIZE" => "1", "POOL_MAX_SIZE" => "5" };
+    assert!(PoolConfig::load_from(&source).is_ok());
+}
+
+#[test]
+fn accepts_plain_messages() {
+    let err = TlsConfig::load_from(&env_map! { "TLS_CERT" => "cert.pem" }).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "validation failed for `TlsConfig`: TLS cert and key must both be set"
+    );
+    assert_eq!(err.key(), None);
+
+    assert!(TlsConfig::load_from(&env_map! {}).is_ok());
+}
+
+#[test]
+fn runs_only_after_fields_load() {
+    let source = env_map! {
+        "POOL_MIN_SIZE" => "many",
+        "POOL_MAX_SIZE" => "5",
+        "TLS_KEY" => "key.pem",
+    };
+    let err = AppConfig::load_from(&source).unwrap_err();
+    let EnvManError::Multiple(errors) = &err else {
+        panic!("expected multiple errors, got {err:?}");
+    };
+    assert_eq!(errors.len(), 2);
+    assert!(
+        matches!(errors[0], EnvManError::Nested { ref error, .. } if matches!(**error, EnvManError::Parse { .. }))
+    );
+    assert!(
+        matches!(errors[1], EnvManError::Nested { ref error, .. } if matches!(**error, EnvManError::StructValidation { .. }))
+    );
+}
@@ -1,4 +1,4 @@
-use envman::EnvMan;
+use envman::{env_map, EnvMan};
 
 // Example: Email validation with custom error
 #[allow(clippy::ptr_arg)]
@@ -59,32 +59,31 @@ struct AdvancedConfig {
 
 #[test]
 fn test_valid_config() {
-    std::env::set_var("APP_ADMIN_EMAIL", "admin@example.com");
-    std::env::set_var("APP_THREAD_COUNT", "8");
-    std::env::set_var("APP_API_URL", "https://api.example.com");
-    std::env::set_var("APP_APP_NAME", "MyApp");
+    let source = env_map! {
+        "APP_ADMIN_EMAIL" => "admin@example.com",
+        "APP_THREAD_COUNT" => "8",
+        "APP_API_URL" => "https://api.example.com",
+        "APP_APP_NAME" => "MyApp",
+    };
 
-    let config = AdvancedConfig::load_from_env().unwrap();
+    let config = AdvancedConfig::load_from(&source).unwrap();
 
     assert_eq!(config.admin_email, "admin@example.com");
     assert_eq!(config.thread_count, 8);
     assert_eq!(config.api_url, "https://api.example.com");
     assert_eq!(config.app_name, "MyApp");
-
-    std::env::remove_var("APP_ADMIN_EMAIL");
-    std::env::remove_var("APP_THREAD_COUNT");
-    std::env::remove_var("APP_API_URL");
-    std::env::remove_var("APP_APP_NAME");
 }
 
 #[test]
 fn test_invalid_email() {
-    std::env::set_var("APP_ADMIN_EMAIL", "not-an-email");
-    std::env::set_var("APP_THREAD_COUNT", "8");
-    std::env::set_var("APP_API_URL", "https://api.example.com");
-    std::env::set_var("APP_APP_NAME", "MyApp");
-
-    let result = AdvancedConfig::load_from_env();
+    let source = env_map! {
+        "APP_ADMIN_EMAIL" => "not-an-email",
+        "APP_THREAD_COUNT" => "8",
+        "APP_API_URL" => "https://api.example.com",
+        "APP_APP_NAME" => "MyApp",
+    };
+
+    let result = AdvancedConfig::load_from(&source);
     assert!(result.is_err());
 
     if let Err(err) = result {
@@ -94,21 +93,18 @@ fn test_invalid_email() {
             "validation failed for environment variable 'APP_ADMIN_EMAIL' with value 'not-an-email': 'not-an-email' is not a valid email address"
         );
     }
-
-    std::env::remove_var("APP_ADMIN_EMAIL");
-    std::env::remove_var("APP_THREAD_COUNT");
-    std::env::remove_var("APP_API_URL");
-    std::env::remove_var("APP_APP_NAME");
 }
 
 #[test]
 fn test_invalid_thread_count_too_high() {
-    std::env::set_var("APP_ADMIN_EMAIL", "admin@example.com");
-    std::env::set_var("APP_THREAD_COUNT", "2000");
-    std::env::set_var("APP_API_URL", "https://api.example.com");
-    std::env::set_var("APP_APP_NAME", "MyApp");
-
-    let result = AdvancedConfig::load_from_env();
+    let source = env_map! {
+        "APP_ADMIN_EMAIL" => "admin@example.com",
+        "APP_THREAD_COUNT" => "2000",
+        "APP_API_URL" => "https://api.example.com",
+        "APP_APP_NAME" => "MyApp",
+    };
+
+    let result = AdvancedConfig::load_from(&source);
     assert!(result.is_err());
 
     if let Err(err) = result {
@@ -118,21 +114,18 @@ fn test_invalid_thread_count_too_high() {
             "validation failed for environment variable 'APP_THREAD_COUNT' with value '2000': Thread count 2000 exceeds maximum of 1024"
         );
     }
-
-    std::env::remove_var("APP_ADMIN_EMAIL");
-    std::env::remove_var("APP_THREAD_COUNT");
-    std::env::remove_var("APP_API_URL");
-    std::env::remove_var("APP_APP_NAME");
 }
 
 #[test]
 fn test_invalid_url() {
-    std::env::set_var("APP_ADMIN_EMAIL", "admin@example.com");
-    std::env::set_var("APP_THREAD_COUNT", "8");
-    std::env::set_var("APP_API_URL", "ftp://api.example.com");
-    std::env::set_var("APP_APP_NAME", "MyApp");
-
-    let result = AdvancedConfig::load_from_env();
+    let source = env_map! {
+        "APP_ADMIN_EMAIL" => "admin@example.com",
+        "APP_THREAD_COUNT" => "8",
+        "APP_API_URL" => "ftp://api.example.com",
+        "APP_APP_NAME" => "MyApp",
+    };
+
+    let result = AdvancedConfig::load_from(&source);
     assert!(result.is_err());
 
     if let Err(err) = result {
@@ -142,21 +135,18 @@ fn test_invalid_url() {
             "validation failed for environment variable 'APP_API_URL' with value 'ftp://api.example.com': URL 'ftp://api.example.com' must start with http:// or https://"
         );
     }
-
-    std::env::remove_var("APP_ADMIN_EMAIL");
-    std::env::remove_var("APP_THREAD_COUNT");
-    std::env::remove_var("APP_API_URL");
-    std::env::remove_var("APP_APP_NAME");
 }
 
 #[test]
 fn test_empty_app_name() {
-    std::env::set_var("APP_ADMIN_EMAIL", "admin@example.com");
-    std::env::set_var("APP_THREAD_COUNT", "8");
-    std::env::set_var("APP_API_URL", "https://api.example.com");
-    std::env::set_var("APP_APP_NAME", "   ");
-
-    let result = AdvancedConfig::load_from_env();
+    let source = env_map! {
+        "APP_ADMIN_EMAIL" => "admin@example.com",
+        "APP_THREAD_COUNT" => "8",
+        "APP_API_URL" => "https://api.example.com",
+        "APP_APP_NAME" => "   ",
+    };
+
+    let result = AdvancedConfig::load_from(&source);
     assert!(result.is_err());
 
     if let Err(err) = result {
@@ -166,25 +156,18 @@ fn test_empty_app_name() {
             "validation failed for environment variable 'APP_APP_NAME' with value '   ': Value cannot be empty"
         );
     }
-
-    std::env::remove_var("APP_ADMIN_EMAIL");
-    std::env::remove_var("APP_THREAD_COUNT");
-    std::env::remove_var("APP_API_URL");
-    std::env::remove_var("APP_APP_NAME");
 }
 
 #[test]
 fn test_default_thread_count() {
-    std::env::set_var("APP_ADMIN_EMAIL", "admin@example.com");
     // Don't set APP_THREAD_COUNT - use default
-    std::env::set_var("APP_API_URL", "https://api.example.com");
-    std::env::set_var("APP_APP_NAME", "MyApp");
+    let source = env_map! {
+        "APP_ADMIN_EMAIL" => "admin@example.com",
+        "APP_API_URL" => "https://api.example.com",
+        "APP_APP_NAME" => "MyApp",
+    };
 
-    let config = AdvancedConfig::load_from_env().unwrap();
+    let config = AdvancedConfig::load_from(&source).unwrap();
 
     assert_eq!(config.thread_count, 4); // Default value
-
-    std::env::remove_var("APP_ADMIN_EMAIL");
-    std::env::remove_var("APP_API_URL");
-    std::env::remove_var("APP_APP_NAME");
 }
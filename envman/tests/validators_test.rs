@@ -0,0 +1,116 @@
+use envman::{env_map, EnvMan, EnvManError};
+
+fn validate_even(value: &u32) -> Result<(), String> {
+    if value % 2 == 0 {
+        Ok(())
+    } else {
+        Err(String::from("must be even"))
+    }
+}
+
+#[derive(EnvMan, Debug)]
+#[envman(prefix = "VAL_")]
+#[allow(dead_code)]
+struct ValidatedConfig {
+    #[envman(range(min = 1, max = 1024), validate = validate_even, default = 4)]
+    threads: u32,
+    #[envman(range(min = -1.5))]
+    offset: f64,
+    #[envman(len(min = 3, max = 8))]
+    name: String,
+    #[envman(len(max = 2), separator = ",", default = Vec::new())]
+    hosts: Vec<String>,
+    #[envman(non_empty)]
+    token: Option<String>,
+    #[envman(one_of = ["debug", "info", "warn"], default = "info")]
+    level: String,
+    #[envman(url)]
+    endpoint: Option<String>,
+    #[envman(email)]
+    admin: Option<String>,
+    #[envman(port)]
+    port: Option<i32>,
+}
+
+fn message(source: &envman::MapSource, key: &str) -> String {
+    let err = ValidatedConfig::load_from(source).unwrap_err();
+    let errors = match err {
+        EnvManError::Multiple(errors) => errors,
+        err => vec![err],
+    };
+    let err = errors
+        .into_iter()
+        .find(|err| err.key() == Some(key))
+        .unwrap_or_else(|| panic!("no error for {key}"));
+    assert!(matches!(err, EnvManError::Validation { .. }));
+    err.to_string()
+}
+
+#[test]
+fn accepts_valid_values() {
+    let source = env_map! {
+        "VAL_OFFSET" => "-1.5",
+        "VAL_NAME" => "api",
+        "VAL_HOSTS" => "a,b",
+        "VAL_TOKEN" => "secret",
+        "VAL_LEVEL" => "warn",
+        "VAL_ENDPOINT" => "https://example.com/v1",
+        "VAL_ADMIN" => "admin@example.com",
+        "VAL_PORT" => "8080",
+    };
+    let config = ValidatedConfig::load_from(&source).unwrap();
+    assert_eq!(config.threads, 4);
+    assert_eq!(config.level, "warn");
+}
+
+#[test]
+fn rejects_invalid_values() {
+    let source = env_map! {
+        "VAL_THREADS" => "2048",
+        "VAL_OFFSET" => "-2",
+        "VAL_NAME" => "ab",
+        "VAL_HOSTS" => "a,b,c",
+        "VAL_TOKEN" => "",
+        "VAL_LEVEL" => "trace",
+        "VAL_ENDPOINT" => "example.com",
+        "VAL_ADMIN" => "admin@localhost",
+        "VAL_PORT" => "70000",
+    };
+    assert_eq!(
+        message(&source, "VAL_THREADS"),
+        "validation failed for environment variable 'VAL_THREADS' with value '2048': must be between 1 and 1024"
+    );
+    assert!(message(&source, "VAL_OFFSET").ends_with(": must be at least -1.5"));
+    assert!(message(&source, "VAL_NAME").ends_with(": length must be between 3 and 8, got 2"));
+    assert!(message(&source, "VAL_HOSTS").ends_with(": length must be at most 2, got 3"));
+    assert!(message(&source, "VAL_TOKEN").ends_with(": must not be empty"));
+    assert!(message(&source, "VAL_LEVEL").ends_with(": must be one of: debug, info, warn"));
+    assert!(
+        message(&source, "VAL_ENDPOINT").ends_with(": must be a URL such as `https://example.com`")
+    );
+    assert!(message(&source, "VAL_ADMIN").ends_with(": must be an email address"));
+    assert!(message(&source, "VAL_PORT").ends_with(": must be a port number between 1 and 65535"));
+}
+
+#[test]
+fn builtin_validators_run_before_validate() {
+    let source = env_map! { "VAL_THREADS" => "3", "VAL_OFFSET" => "0", "VAL_NAME" => "api" };
+    assert!(message(&source, "VAL_THREADS").ends_with(": must be even"));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn matches_pattern() {
+    #[derive(EnvMan, Debug)]
+    #[allow(dead_code)]
+    struct SlugConfig {
+        #[envman(matches = r"^[a-z]+(-[a-z]+)*$")]
+        slug: String,
+    }
+
+    assert!(SlugConfig::load_from(&env_map! { "SLUG" => "my-app" }).is_ok());
+    let err = SlugConfig::load_from(&env_map! { "SLUG" => "My App" }).unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with(": must match the pattern `^[a-z]+(-[a-z]+)*$`"));
+}
@@ -10,10 +10,14 @@ use syn::{parse_macro_input, DeriveInput};
 
 /// Automatically implements [`envman::EnvMan`]
 ///
+/// The generated `load_from` reads every variable through the given `envman::EnvSource`,
+/// and `load_from_env` reads the environment of the current process.
+///
 /// # Note
 /// - If the field is `Option`, the default value is `None`.
 /// - If the field has a `rename` attribute, the field name is not affected by `suffix`, `prefix`, and `rename_all`.
 /// - The `rename_all` attribute affects only the base field name transformation and does not influence the application of `prefix` or `suffix`.
+/// - Every field is loaded even if another one fails, and all failures are reported together as `EnvManError::Multiple`.
 ///
 /// # Struct Attributes:
 ///
@@ -29,21 +33,202 @@ use syn::{parse_macro_input, DeriveInput};
 /// ### suffix: `suffix = "suffix"` (default: None)
 /// Suffix to all fields.
 ///
+/// ### nesting_separator: `nesting_separator = "__"` (default: None)
+/// Build the keys of `nest` fields from the path of field names, joined by this separator.
+/// The prefixes of nested structs are ignored, and the mode applies to the whole tree below this struct.
+/// With `prefix = "APP__"`, the field `database.pool.max_size` is read from `APP__DATABASE__POOL__MAX_SIZE`.
+///
+/// ### validate_struct: `validate_struct = check_config` (default: None)
+/// Validation across fields with signature `fn(&Self) -> Result<(), E>` where `E: Into<envman::StructValidationError>`
+/// (`String` and `&str` also work). It runs after every field has loaded, and its error is reported as
+/// `EnvManError::StructValidation` with the keys of the fields named by `StructValidationError::field`.
+///
+/// ### group: `group(name = "auth", exactly_one = [api_key, oauth_token])` (default: None)
+/// Check how many variables of a group of fields are set, with `exactly_one`, `at_least_one` or `at_most_one`.
+/// Members must be `Option` fields or have a `default`, and the attribute can be given several times.
+/// A broken rule is reported as `EnvManError::Group` with the keys of every member and of the set ones.
+///
+/// ### key: `key = "PORT"` (newtype structs only)
+/// The key of the field of a newtype struct such as `struct HttpPort(u16);`. See [Tuple Structs](#tuple-structs).
+///
+/// ### list_syntax: `list_syntax = "quoted"` (default: "plain")
+/// The `list_syntax` of the `separator` fields without their own. See the field attribute.
+///
+/// ### empty / trim: `empty = "unset"`, `trim` (default: "value", false)
+/// The `empty` and `trim` of the fields without their own, and of the `tag` of an enum. See the field attributes.
+///
+/// ### case_insensitive: `case_insensitive` (default: false)
+/// Match keys ignoring case, so `DB_URL` is also read from `db_url` or `Db_Url`. The keys of the source are read
+/// once per load with `envman::CaseInsensitiveSource`, which also applies to the `nest` fields. A key read with
+/// several cases, including the keys of `nest, map` and `nest, indexed` entries, is reported as
+/// `EnvManError::AmbiguousKey` together with the other errors, and fields whose keys only differ in case are
+/// rejected at compile time. Names of `nest, map` entries are read in lowercase.
+///
+/// ### tag: `tag = "KIND"` (enums only, required)
+/// The variable selecting the variant of an enum. See [Enums](#enums).
+///
+/// ### inherit_prefix: `inherit_prefix` (default: false)
+/// When loaded as a `nest` field, prepend the prefix of the parent struct to this struct's prefix.
+/// With `prefix = "APP_"` on the parent and `prefix = "DB_"` here, the field `url` is read from `APP_DB_URL`.
+///
+/// # Tuple Structs
+///
+/// Fields of tuple structs have no name, so each takes its key from a `key = "HOST"` field attribute,
+/// or from the `key` struct attribute for a newtype. The key is affected by `prefix` and `suffix` but
+/// not by `rename_all`, and all other field attributes apply. Newtypes can be used as `nest` fields.
+///
+/// ```rust
+/// # use envman_derive::EnvMan;
+/// # mod envman {
+/// #   include!("../../envman/src/def.rs");
+/// # }
+/// # use envman::EnvMan;
+/// #[derive(EnvMan)]
+/// #[envman(key = "HTTP_PORT")]
+/// struct HttpPort(#[envman(default = 8080)] u16);
+///
+/// #[derive(EnvMan)]
+/// #[envman(prefix = "SERVER_")]
+/// struct Endpoint(#[envman(key = "HOST")] String, #[envman(key = "PORT")] u16);
+///
+/// #[allow(unused_unsafe)]
+/// unsafe {
+///     std::env::set_var("SERVER_HOST", "localhost");
+///     std::env::set_var("SERVER_PORT", "443");
+/// }
+///
+/// assert_eq!(HttpPort::load_from_env().unwrap().0, 8080);
+/// let endpoint = Endpoint::load_from_env().unwrap();
+/// assert_eq!((endpoint.0.as_str(), endpoint.1), ("localhost", 443));
+/// ```
+///
+/// # Enums
+///
+/// An enum with a `tag = "KIND"` attribute selects its variant by the value of the `KIND` variable
+/// (after `prefix`). The value of a variant is its name converted with `rename_all`, or its `rename`,
+/// and is matched case-insensitively. A unit variant has no variables, and the field of a newtype
+/// variant is loaded with the same prefix as the enum. Mark a variant with `default` to use it when the
+/// tag variable is not set. An unknown value is reported as `EnvManError::UnknownVariant`.
+///
+/// ```rust
+/// # use envman_derive::EnvMan;
+/// # mod envman {
+/// #   include!("../../envman/src/def.rs");
+/// # }
+/// # use envman::EnvMan;
+/// #[derive(EnvMan)]
+/// #[envman(tag = "STORAGE_KIND")]
+/// enum Storage {
+///     S3(S3Config),
+///     #[envman(default)]
+///     Memory,
+/// }
+///
+/// #[derive(EnvMan)]
+/// #[envman(prefix = "S3_")]
+/// struct S3Config {
+///     bucket: String,
+/// }
+///
+/// #[allow(unused_unsafe)]
+/// unsafe {
+///     std::env::set_var("STORAGE_KIND", "s3");
+///     std::env::set_var("S3_BUCKET", "assets");
+/// }
+///
+/// let Storage::S3(config) = Storage::load_from_env().unwrap() else { unreachable!() };
+/// assert_eq!(config.bucket, "assets");
+/// ```
+///
 /// # Field Attributes:
 ///
 /// ### rename : `rename = "new name"` (default: UPPER_CASE)
 ///
+/// ### alias: `alias = "DATABASE_URL"` (default: None)
+/// Keys tried in order when the key of the field is not set, used as they are without `prefix` or `suffix`.
+/// Can be given several times. Errors of the field name the key that was read.
+///
 /// ### parser: `parser = utils::default_parser` (default: FromStr::from_str)
 /// Parser type is `fn(&str) -> Result<T, E>` and `E` must implement `std::error::Error`.
 ///
+/// ### os: `os` (default: false)
+/// Read the variable as an `OsString` and convert it with `From<OsString>`, for `OsString` and `PathBuf` fields.
+/// The value is kept byte-exact even if it is not valid unicode.
+///
+/// Without `os`, a value that is not valid unicode is reported as `EnvManError::NotUnicode`.
+///
+/// ### empty: `empty = "unset"` (default: "value")
+/// What a variable set to an empty value such as `FOO=` means. `"unset"` treats it as not set, so the `default`
+/// or `None` is used, `"error"` rejects it with `EnvManError::Empty`, and `"value"` parses the empty string.
+///
+/// ### trim: `trim` or `trim = false` (default: false)
+/// Remove leading and trailing whitespace from the value before it is checked by `empty` and parsed.
+/// Not allowed with `os`.
+///
 /// ### separator: `separator = ","` (default: None)
 /// For Vec/array types, split the environment variable by this separator.
+/// Any `FromIterator` collection such as `HashSet<T>`, `BTreeSet<T>`, `VecDeque<T>` or `Box<[T]>` works,
+/// with the element type taken from its first generic argument, and `[T; N]` requires exactly `N` elements.
 /// Example: `ALLOWED_HOSTS=host1,host2,host3` becomes `vec!["host1", "host2", "host3"]`
 ///
+/// - validate_each: `validate_each = my_validator` Validate every element with `fn(&T) -> Result<(), E>` where `E: Display`.
+/// - unique: `unique` Reject an element equal to an earlier one.
+/// - sorted: `sorted` Reject an element less than the one before it.
+/// - min_items / max_items: `min_items = 1`, `max_items = 8` Limit the number of elements, reported as `EnvManError::Count`.
+///
+/// A failing element is reported as `EnvManError::ElementValidation` with its index and text.
+///
+/// ### list_syntax: `list_syntax = "quoted"` (default: "plain")
+/// How `separator` fields are split, also allowed on the struct as the default of its fields.
+/// `"plain"` splits at every separator and trims the elements. `"quoted"` keeps separators and whitespace
+/// in `"double"` or `'single'` quotes, and a backslash escapes a quote, a backslash or the separator while
+/// other backslashes such as in `\d+` are kept. Empty elements are kept, and an empty value is an empty list. `"json"` also reads a value starting with `[` as a JSON array
+/// such as `["a,b", "c"]`, and splits other values like `"quoted"`. An invalid list is reported as
+/// `EnvManError::ListSyntax`.
+///
+/// ### tuple_separator: `tuple_separator = ":"` (default: None)
+/// With `separator`, split every element into a tuple, e.g. `Vec<(String, u16)>` from `HOSTS=a:80,b:443`.
+/// An element with the wrong number of parts is reported as `EnvManError::TupleArity`.
+///
+/// ### inner_separator: `inner_separator = ","` (default: None)
+/// With `separator`, split every element into a collection, e.g. `Vec<Vec<u8>>` from `SHARDS=1,2;3` with `separator = ";"`.
+///
+/// Parse errors of a part of a tuple, pair or inner collection name its `position` in the element.
+///
+/// ### kv_separator: `kv_separator = "="` (default: None)
+/// With `separator`, parse a `HashMap<K, V>` or `BTreeMap<K, V>` from pairs such as `LABELS=team=core,tier=1`.
+/// Each pair is split at the first `kv_separator`, and the key and the value are parsed with `FromStr`.
+/// A pair without it is reported as `EnvManError::MalformedPair`, and a repeated key as `EnvManError::DuplicateKey`.
+///
 /// ### validate: `validate = my_validator` (default: None)
 /// Custom validation function with signature `fn(&T) -> Result<(), E>` where `E: Display`.
 /// The error message from the Result will be included in the validation error.
 ///
+/// ### Built-in validators
+/// Built-in validation from `envman::validators`, reported as `EnvManError::Validation` like `validate`.
+/// They run in the order given and before `validate`.
+///
+/// - range: `range(min = 1, max = 1024)` The value is within the bounds (either may be omitted).
+/// - len: `len(min = 1, max = 64)` The number of characters of a string, or elements of a `Vec`, is within the bounds.
+/// - non_empty: `non_empty` The string or `Vec` is not empty.
+/// - one_of: `one_of = ["debug", "info"]` The value, formatted with `Display`, is one of these.
+/// - matches: `matches = r"^[a-z]+$"` The value matches the regular expression (requires the `regex` feature).
+/// - url: `url` The value looks like `scheme://host...`.
+/// - email: `email` The value looks like `local@domain.tld`.
+/// - port: `port` The value is a port number between 1 and 65535.
+///
+/// ### Conditional requirements
+/// Rules on other variables, checked before the field is loaded, and every rule can be given several times.
+/// A name matching a field of the struct, by its `rename` or its name with `rename_all` applied, stands for
+/// the key that field is read from, including `suffix` and `alias`. Other names get the struct prefix and suffix.
+///
+/// - required_if: `required_if(TLS_ENABLED = "true")` The variable is required when `TLS_ENABLED` has this value.
+/// - required_unless: `required_unless = "DATABASE_HOST"` The variable is required unless `DATABASE_HOST` is set.
+/// - conflicts_with: `conflicts_with = "API_KEY_FILE"` The variable must not be set together with `API_KEY_FILE`.
+///
+/// `required_if` and `required_unless` need an `Option` field or a `default`, and are reported as
+/// `EnvManError::Required`. `conflicts_with` is reported as `EnvManError::Conflict`.
+///
 /// ### secret: `secret` (default: false)
 /// Mark this field as secret. When used with `EnvManDebug`, the value will be masked as "***".
 ///
@@ -62,6 +247,21 @@ use syn::{parse_macro_input, DeriveInput};
 ///
 /// ### nest: `nest` (default: false)
 /// If the field implements `envman::EnvMan`, it will be parsed as a struct.
+/// Errors of the nested struct are reported as `EnvManError::Nested` with the path of the field.
+///
+/// With `Option` or `default`, the field is `None` or the default only when none of the variables of the nested struct are set.
+/// If some of them are set, the nested struct is loaded and its errors are reported.
+///
+/// - nest_prefix: `prefix = "PRIMARY_DB_"` (default: None)
+///   Replace the prefix of the nested struct, so that the same struct can be loaded several times.
+/// - indexed: `indexed` (default: false)
+///   Load a `Vec` of nested structs from indexed keys. The field `replicas` reads
+///   `REPLICAS_0_*`, `REPLICAS_1_*`, ... (`nesting_separator` replaces `_`).
+///   Indices must start at 0 without gaps. Use `min = 1` and `max = 8` to limit the number of entries.
+/// - map: `map` (default: false)
+///   Load a `HashMap<String, T>` or `BTreeMap<String, T>` of nested structs. The field `tenants` reads
+///   `TENANTS_{NAME}_{FIELD}` for every `{NAME}` found in the variables, and `{NAME}` is converted back
+///   from the `rename_all` rule of `T` to snake_case for the map key. `min` and `max` are also supported.
 ///
 /// # Example
 /// ```rust
@@ -119,6 +319,52 @@ pub fn derive_envman(input: proc_macro::TokenStream) -> proc_macro::TokenStream
         .into()
 }
 
+/// Implements `FromStr` for an enum of unit variants, to use it as the type of a field
+///
+/// The value of a variant is its name converted with `rename_all` (default: SCREAMING_SNAKE_CASE),
+/// or its `rename`, and `alias = "..."` adds more values (repeatable). Values are matched case-insensitively.
+/// An unknown value is rejected with `envman::ParseEnumError`, which lists the accepted values and
+/// becomes the source of `EnvManError::Parse`.
+///
+/// # Example
+/// ```rust
+/// # use envman_derive::{EnvMan, EnvManEnum};
+/// # mod envman {
+/// #   include!("../../envman/src/def.rs");
+/// # }
+/// # use envman::EnvMan;
+///
+/// #[derive(EnvManEnum, Debug, PartialEq)]
+/// #[envman(rename_all = "lowercase")]
+/// enum LogFormat {
+///     Json,
+///     #[envman(alias = "text")]
+///     Plain,
+/// }
+///
+/// #[derive(EnvMan)]
+/// struct Config {
+///     log_format: LogFormat,
+/// }
+///
+/// #[allow(unused_unsafe)]
+/// unsafe {
+///     std::env::set_var("LOG_FORMAT", "TEXT");
+/// }
+///
+/// let config = Config::load_from_env().unwrap();
+/// assert_eq!(config.log_format, LogFormat::Plain);
+///
+/// let err = "yaml".parse::<LogFormat>().unwrap_err();
+/// assert_eq!(err.to_string(), "unknown value 'yaml', expected one of: json, plain, text");
+/// ```
+#[proc_macro_derive(EnvManEnum, attributes(envman))]
+pub fn derive_envman_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    envman_derive_internals::derive_envman_enum(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 /// Derives Debug with masking support for fields marked with `#[envman(secret)]`
 ///
 /// # Example
@@ -1,36 +1,99 @@
-use crate::{check_duplicate, require_lit_str, EnvManStructArgs};
+use crate::{check_duplicate, require_lit_str, EmptyPolicy, EnvManStructArgs, ListSyntax};
 use convert_case::Casing;
 use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn::{punctuated::Punctuated, spanned::Spanned, Expr, Meta, Token, Type};
 
 pub(crate) struct EnvManFieldArgs {
+    /// The `rename` value, or the field name with `rename_all` and `suffix` applied.
+    /// In the latter case the struct prefix is prepended when loading.
     pub name: String,
+    pub renamed: bool,
+    /// Keys tried in order when the variable `name` is not set, not affected by `prefix`
+    pub aliases: Vec<String>,
+    /// The `rename` value, or the field name with `rename_all` applied, naming the nested struct with a `nesting_separator`
+    pub segment: String,
+    pub field: String,
+    /// Span of the field, for errors about its attributes
+    pub span: proc_macro2::Span,
+    pub value_ty: Type,
     pub parser: Option<TokenStream>,
     pub default: Option<TokenStream>,
     pub test: Option<TokenStream>,
     pub alltime_parse: bool,
     pub is_option: bool,
     pub nest: bool,
+    pub nest_prefix: Option<String>,
+    pub indexed: bool,
+    pub map: bool,
+    pub min: Option<TokenStream>,
+    pub max: Option<TokenStream>,
+    pub os: bool,
     pub separator: Option<String>,
+    pub kv_separator: Option<String>,
+    /// `list_syntax` of the field, or of the struct if the field has no `list_syntax`
+    pub list_syntax: ListSyntax,
+    /// Whether the field has a `list_syntax`
+    pub list_syntax_set: bool,
+    pub tuple_separator: Option<String>,
+    pub inner_separator: Option<String>,
+    /// `empty` of the field, or of the struct if the field has no `empty`
+    pub empty: EmptyPolicy,
+    /// `trim` of the field, or of the struct if the field has no `trim` and is not `os`
+    pub trim: bool,
     pub validate: Option<TokenStream>,
+    pub validate_each: Option<TokenStream>,
+    pub unique: bool,
+    pub sorted: bool,
+    pub min_items: Option<TokenStream>,
+    pub max_items: Option<TokenStream>,
+    /// Calls of the built-in validators in `envman::validators`, taking `&parsed_value`
+    pub validators: Vec<TokenStream>,
     pub secret: bool,
+    /// Keys and values of `required_if(KEY = "value")`, relative to the struct prefix
+    pub required_if: Vec<(String, String)>,
+    pub required_unless: Vec<String>,
+    pub conflicts_with: Vec<String>,
 }
 
 /// Find the value of a #[envman(name = "...")] attribute.
 pub(crate) fn attr(
     field: &syn::Field,
+    index: usize,
     struct_arg: &EnvManStructArgs,
 ) -> syn::Result<EnvManFieldArgs> {
     let mut rename: Option<String> = None;
+    let mut key: Option<String> = None;
+    let mut aliases: Vec<String> = Vec::new();
     let mut parser: Option<TokenStream> = None;
     let mut default: Option<TokenStream> = None;
     let mut test: Option<TokenStream> = None;
     let mut alltime_parse = false;
     let mut nest = false;
+    let mut nest_prefix: Option<String> = None;
+    let mut indexed = false;
+    let mut map = false;
+    let mut min: Option<TokenStream> = None;
+    let mut max: Option<TokenStream> = None;
+    let mut os = false;
     let mut separator: Option<String> = None;
+    let mut kv_separator: Option<String> = None;
+    let mut list_syntax: Option<ListSyntax> = None;
+    let mut tuple_separator: Option<String> = None;
+    let mut inner_separator: Option<String> = None;
+    let mut empty: Option<EmptyPolicy> = None;
+    let mut trim: Option<bool> = None;
     let mut validate: Option<TokenStream> = None;
+    let mut validators: Vec<TokenStream> = Vec::new();
+    let mut validate_each: Option<TokenStream> = None;
+    let mut unique = false;
+    let mut sorted = false;
+    let mut min_items: Option<TokenStream> = None;
+    let mut max_items: Option<TokenStream> = None;
     let mut secret = false;
+    let mut required_if: Vec<(String, String)> = Vec::new();
+    let mut required_unless: Vec<String> = Vec::new();
+    let mut conflicts_with: Vec<String> = Vec::new();
 
     for attr in &field.attrs {
         if !attr.path().is_ident("envman") {
@@ -48,6 +111,18 @@ pub(crate) fn attr(
 
                     rename = Some(string);
                 }
+                Meta::NameValue(meta) if meta.path.is_ident("key") => {
+                    check_duplicate!(meta.span(), key);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    key = Some(string);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("alias") => {
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    aliases.push(string);
+                }
                 Meta::Path(ref path) if path.is_ident("default") => {
                     check_duplicate!(meta.span(), default);
 
@@ -94,6 +169,71 @@ pub(crate) fn attr(
                     check_duplicate!(path.span(), nest, nest);
                     nest = true;
                 }
+                Meta::NameValue(meta) if meta.path.is_ident("prefix") => {
+                    check_duplicate!(meta.span(), nest_prefix);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    nest_prefix = Some(string);
+                }
+                Meta::Path(ref path) if path.is_ident("indexed") => {
+                    check_duplicate!(path.span(), indexed, indexed);
+                    indexed = true;
+                }
+                Meta::Path(ref path) if path.is_ident("map") => {
+                    check_duplicate!(path.span(), map, map);
+                    map = true;
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("min") => {
+                    check_duplicate!(meta.span(), min);
+
+                    min = Some(meta.value.into_token_stream());
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("max") => {
+                    check_duplicate!(meta.span(), max);
+
+                    max = Some(meta.value.into_token_stream());
+                }
+                Meta::Path(ref path) if path.is_ident("os") => {
+                    check_duplicate!(path.span(), os, os);
+                    os = true;
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("list_syntax") => {
+                    check_duplicate!(meta.span(), list_syntax);
+
+                    list_syntax = Some(crate::struct_attr::list_syntax_attr(&meta)?);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("empty") => {
+                    check_duplicate!(meta.span(), empty);
+
+                    empty = Some(crate::struct_attr::empty_policy(&meta)?);
+                }
+                Meta::Path(_) | Meta::NameValue(_) if meta.path().is_ident("trim") => {
+                    check_duplicate!(meta.span(), trim);
+
+                    trim = Some(crate::struct_attr::trim_attr(&meta)?);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("kv_separator") => {
+                    check_duplicate!(meta.span(), kv_separator);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    kv_separator = Some(string);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("tuple_separator") => {
+                    check_duplicate!(meta.span(), tuple_separator);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    tuple_separator = Some(string);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("inner_separator") => {
+                    check_duplicate!(meta.span(), inner_separator);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    inner_separator = Some(string);
+                }
                 Meta::NameValue(meta) if meta.path.is_ident("separator") => {
                     check_duplicate!(meta.span(), separator);
 
@@ -111,27 +251,166 @@ pub(crate) fn attr(
 
                     return Err(syn::Error::new_spanned(meta, "expected path"));
                 }
+                Meta::NameValue(meta) if meta.path.is_ident("validate_each") => {
+                    check_duplicate!(meta.span(), validate_each);
+
+                    if let Expr::Path(path) = &meta.value {
+                        validate_each = Some(path.to_token_stream());
+                        continue;
+                    }
+
+                    return Err(syn::Error::new_spanned(meta, "expected path"));
+                }
+                Meta::Path(ref path) if path.is_ident("unique") => {
+                    check_duplicate!(path.span(), unique, unique);
+                    unique = true;
+                }
+                Meta::Path(ref path) if path.is_ident("sorted") => {
+                    check_duplicate!(path.span(), sorted, sorted);
+                    sorted = true;
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("min_items") => {
+                    check_duplicate!(meta.span(), min_items);
+
+                    min_items = Some(meta.value.into_token_stream());
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("max_items") => {
+                    check_duplicate!(meta.span(), max_items);
+
+                    max_items = Some(meta.value.into_token_stream());
+                }
+                Meta::List(ref list)
+                    if list.path.is_ident("range") || list.path.is_ident("len") =>
+                {
+                    let (min, max) = bounds(list)?;
+                    let validator = &list.path;
+                    validators.push(quote::quote! {
+                        envman::validators::#validator(&parsed_value, #min, #max)
+                    });
+                }
+                Meta::Path(ref path)
+                    if path.is_ident("non_empty")
+                        || path.is_ident("url")
+                        || path.is_ident("email")
+                        || path.is_ident("port") =>
+                {
+                    validators.push(quote::quote! {
+                        envman::validators::#path(&parsed_value)
+                    });
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("one_of") => {
+                    let Expr::Array(array) = &meta.value else {
+                        return Err(syn::Error::new_spanned(meta, "expected array of strings"));
+                    };
+                    let allowed = array
+                        .elems
+                        .iter()
+                        .map(|elem| require_lit_str(elem, elem))
+                        .collect::<syn::Result<Vec<_>>>()?;
+                    validators.push(quote::quote! {
+                        envman::validators::one_of(&parsed_value, &[#( #allowed ),*])
+                    });
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("matches") => {
+                    if !cfg!(feature = "regex") {
+                        return Err(syn::Error::new_spanned(
+                            meta,
+                            "`matches` requires the `regex` feature of envman",
+                        ));
+                    }
+                    let pattern = require_lit_str(&meta, &meta.value)?;
+                    validators.push(quote::quote! {
+                        envman::validators::matches(&parsed_value, #pattern)
+                    });
+                }
                 Meta::Path(ref path) if path.is_ident("secret") => {
                     check_duplicate!(path.span(), secret, secret);
                     secret = true;
                 }
+                Meta::List(ref list) if list.path.is_ident("required_if") => {
+                    let condition: syn::MetaNameValue = list.parse_args()?;
+                    let other = condition
+                        .path
+                        .get_ident()
+                        .ok_or_else(|| syn::Error::new_spanned(&condition.path, "expected key"))?
+                        .to_string();
+                    let value = require_lit_str(&condition, &condition.value)?;
+
+                    required_if.push((other, value));
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("required_unless") => {
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    required_unless.push(string);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("conflicts_with") => {
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    conflicts_with.push(string);
+                }
                 _ => return Err(syn::Error::new_spanned(meta, "unexpected attribute")),
             }
         }
     }
+    if nest && !aliases.is_empty() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`alias` is not allowed when `nest` is true",
+        ));
+    }
+    if nest && (empty.is_some() || trim.is_some()) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`empty` and `trim` are not allowed when `nest` is true",
+        ));
+    }
+    if os && trim == Some(true) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`trim` is not allowed when `os` is true",
+        ));
+    }
+    let renamed = rename.is_some();
+    let (field_name, segment) = match &field.ident {
+        Some(ident) => {
+            if key.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "`key` is only allowed on fields of tuple structs, use `rename` instead",
+                ));
+            }
+            let field_name = unraw(ident);
+            let segment = match &rename {
+                Some(x) => x.clone(),
+                None => field_name.to_case(struct_arg.rename_all),
+            };
+            (field_name, segment)
+        }
+        // Fields of tuple structs have no name to derive the key from
+        None => {
+            if key.is_some() && struct_arg.key.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "duplicate `key` attribute. the struct already has a `key`",
+                ));
+            }
+            let segment = match (&rename, key.or_else(|| struct_arg.key.clone())) {
+                (Some(x), _) => x.clone(),
+                (None, Some(key)) => key,
+                (None, None) => {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "fields of tuple structs require a `key` or `rename` attribute",
+                    ))
+                }
+            };
+            (index.to_string(), segment)
+        }
+    };
     let name = match rename {
         Some(x) => x,
         None => {
-            let mut name = unraw(
-                field
-                    .ident
-                    .as_ref()
-                    .ok_or_else(|| syn::Error::new_spanned(field, "field must have a name"))?,
-            );
-            name = name.to_case(struct_arg.rename_all);
-            if let Some(prefix) = &struct_arg.prefix {
-                name = format!("{prefix}{name}");
-            };
+            let mut name = segment.clone();
             if let Some(suffix) = &struct_arg.suffix {
                 name = format!("{name}{suffix}");
             };
@@ -140,18 +419,78 @@ pub(crate) fn attr(
     };
     Ok(EnvManFieldArgs {
         name,
+        renamed,
+        aliases,
+        segment,
+        field: field_name,
+        span: field.span(),
+        value_ty: option_inner(&field.ty).unwrap_or(&field.ty).clone(),
         default,
         test,
         alltime_parse,
         is_option: is_option(&field.ty),
         parser,
         nest,
+        nest_prefix,
+        indexed,
+        map,
+        min,
+        max,
+        os,
         separator,
+        kv_separator,
+        list_syntax_set: list_syntax.is_some(),
+        list_syntax: list_syntax.unwrap_or(struct_arg.list_syntax),
+        tuple_separator,
+        inner_separator,
+        empty: empty.unwrap_or(struct_arg.empty),
+        // `OsString` cannot be trimmed
+        trim: trim.unwrap_or(struct_arg.trim && !os),
         validate,
+        validate_each,
+        unique,
+        sorted,
+        min_items,
+        max_items,
+        validators,
         secret,
+        required_if,
+        required_unless,
+        conflicts_with,
     })
 }
 
+/// Parse the `min` and `max` of `range(min = 1, max = 1024)` into `Option` expressions.
+fn bounds(list: &syn::MetaList) -> syn::Result<(TokenStream, TokenStream)> {
+    let mut min: Option<Expr> = None;
+    let mut max: Option<Expr> = None;
+
+    let nested = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+    for meta in nested {
+        match meta {
+            Meta::NameValue(meta) if meta.path.is_ident("min") => {
+                check_duplicate!(meta.span(), min);
+                min = Some(meta.value);
+            }
+            Meta::NameValue(meta) if meta.path.is_ident("max") => {
+                check_duplicate!(meta.span(), max);
+                max = Some(meta.value);
+            }
+            _ => return Err(syn::Error::new_spanned(meta, "unexpected attribute")),
+        }
+    }
+
+    if min.is_none() && max.is_none() {
+        return Err(syn::Error::new_spanned(list, "expected `min` or `max`"));
+    }
+    let bound = |expr: Option<Expr>| match expr {
+        Some(expr) => quote::quote! { Some(#expr) },
+        None => quote::quote! { None },
+    };
+    Ok((bound(min), bound(max)))
+}
+
 fn is_option(ty: &Type) -> bool {
     match get_last_path_segment(ty) {
         Some(seg) => seg.ident == "Option",
@@ -159,6 +498,20 @@ fn is_option(ty: &Type) -> bool {
     }
 }
 
+/// The `T` of `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    if !is_option(ty) {
+        return None;
+    }
+    match &get_last_path_segment(ty)?.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn get_last_path_segment(ty: &Type) -> Option<&syn::PathSegment> {
     match ty {
         Type::Path(path) => path.path.segments.last(),
@@ -8,13 +8,14 @@ pub(crate) fn derive_debug(input: &DeriveInput) -> syn::Result<TokenStream> {
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    let fields = match &input.data {
+    let (fields, named) = match &input.data {
         Data::Struct(data) => match &data.fields {
-            Fields::Named(fields) => &fields.named,
-            _ => {
+            Fields::Named(fields) => (&fields.named, true),
+            Fields::Unnamed(fields) => (&fields.unnamed, false),
+            Fields::Unit => {
                 return Err(syn::Error::new_spanned(
                     input,
-                    "EnvManDebug only supports structs with named fields",
+                    "EnvManDebug only supports structs with fields",
                 ))
             }
         },
@@ -30,30 +31,45 @@ pub(crate) fn derive_debug(input: &DeriveInput) -> syn::Result<TokenStream> {
 
     let field_formatters: Vec<_> = fields
         .iter()
-        .map(|field| {
-            let field_args = attr::attr(field, &struct_arg)?;
-            let field_name = &field.ident;
-            let field_name_str = field_name
-                .as_ref()
-                .map(|i| i.to_string())
-                .unwrap_or_default();
-
-            if field_args.secret {
-                Ok(quote! {
-                    .field(#field_name_str, &"***")
-                })
+        .enumerate()
+        .map(|(index, field)| {
+            let field_args = attr::attr(field, index, &struct_arg)?;
+            let value = if field_args.secret {
+                quote! { &"***" }
             } else {
-                Ok(quote! {
-                    .field(#field_name_str, &self.#field_name)
-                })
+                match &field.ident {
+                    Some(field_name) => quote! { &self.#field_name },
+                    None => {
+                        let index = syn::Index::from(index);
+                        quote! { &self.#index }
+                    }
+                }
+            };
+
+            match &field.ident {
+                Some(field_name) => {
+                    let field_name_str = field_name.to_string();
+                    Ok(quote! {
+                        .field(#field_name_str, #value)
+                    })
+                }
+                None => Ok(quote! {
+                    .field(#value)
+                }),
             }
         })
         .collect::<syn::Result<Vec<_>>>()?;
 
+    let debug = if named {
+        quote! { f.debug_struct(stringify!(#name)) }
+    } else {
+        quote! { f.debug_tuple(stringify!(#name)) }
+    };
+
     Ok(quote! {
         impl #impl_generics std::fmt::Debug for #name #ty_generics #where_clause {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.debug_struct(stringify!(#name))
+                #debug
                     #(#field_formatters)*
                     .finish()
             }
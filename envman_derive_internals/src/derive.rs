@@ -1,22 +1,110 @@
 use quote::quote;
 
-use crate::attr::EnvManFieldArgs;
+use crate::{attr::EnvManFieldArgs, EmptyPolicy, EnvManStructArgs, ListSyntax};
 
-pub(crate) fn derive(args: EnvManFieldArgs) -> syn::Result<proc_macro2::TokenStream> {
+/// A field of the struct that `required_if`, `required_unless` and `conflicts_with` can name
+pub(crate) struct Sibling {
+    /// The `rename` value, or the field name with `rename_all` applied
+    pub segment: String,
+    pub field: String,
+    /// The field name with `rename_all` applied, even if the field has a `rename`
+    pub cased: String,
+    /// Expression of the key the field is read from
+    pub key: proc_macro2::TokenStream,
+    pub empty: EmptyPolicy,
+    pub trim: bool,
+}
+
+impl Sibling {
+    pub(crate) fn new(args: &EnvManFieldArgs, rename_all: convert_case::Case<'static>) -> Self {
+        Self {
+            segment: args.segment.clone(),
+            field: args.field.clone(),
+            cased: convert_case::Casing::to_case(&args.field, rename_all),
+            key: resolved_key(args),
+            empty: args.empty,
+            trim: args.trim,
+        }
+    }
+}
+
+/// `siblings` are the fields of the struct, whose keys and `empty` and `trim` are used
+/// for the variables named by `required_if`, `required_unless` and `conflicts_with`.
+pub(crate) fn derive(
+    args: EnvManFieldArgs,
+    struct_arg: &EnvManStructArgs,
+    siblings: &[Sibling],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &struct_arg.name;
+    let resolved_key = resolved_key(&args);
     let EnvManFieldArgs {
         name,
+        renamed,
+        aliases: _,
+        segment,
+        field,
+        span,
+        value_ty,
         parser,
         default,
         test,
         alltime_parse,
         is_option,
         nest,
+        nest_prefix,
+        indexed,
+        map,
+        min,
+        max,
+        os,
         separator,
+        kv_separator,
+        list_syntax,
+        list_syntax_set,
+        tuple_separator,
+        inner_separator,
+        empty,
+        trim: trim_value,
         validate,
+        validate_each,
+        unique,
+        sorted,
+        min_items,
+        max_items,
+        validators,
         secret: _,
+        required_if,
+        required_unless,
+        conflicts_with,
     } = args;
 
+    let conditional = !required_if.is_empty() || !required_unless.is_empty();
+    if conditional && !is_option && default.is_none() {
+        return Err(syn::Error::new(
+            span,
+            "`required_if` and `required_unless` are only allowed on `Option` fields or fields with `default`",
+        ));
+    }
+
     if nest {
+        if !validators.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                "validation attributes are not allowed when `nest` is true",
+            ));
+        }
+        if conditional || !conflicts_with.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                "`required_if`, `required_unless` and `conflicts_with` are not allowed when `nest` is true",
+            ));
+        }
+        if os {
+            return Err(syn::Error::new(
+                span,
+                "`os` is not allowed when `nest` is true",
+            ));
+        }
         if parser.is_some() {
             return Err(syn::Error::new_spanned(
                 parser,
@@ -24,27 +112,143 @@ pub(crate) fn derive(args: EnvManFieldArgs) -> syn::Result<proc_macro2::TokenStr
             ));
         }
 
-        let load_from_env = quote! {
-            envman::EnvMan::load_from_env()
+        if (indexed || map) && nest_prefix.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`prefix` is not allowed when `indexed` or `map` is true",
+            ));
+        }
+        if indexed && map {
+            return Err(syn::Error::new(
+                span,
+                "`indexed` and `map` cannot be used together",
+            ));
+        }
+        if !(indexed || map) && (min.is_some() || max.is_some()) {
+            return Err(syn::Error::new(
+                span,
+                "`min` and `max` are only allowed when `indexed` or `map` is true",
+            ));
+        }
+
+        let (load_from, is_present) = if indexed {
+            let count_check = count_check(min.as_ref(), max.as_ref());
+            let load_from = quote! {
+                {
+                    let indices = __source.indices(&context, separator);
+                    if let Some(index) = (0..indices.len()).find(|i| indices[*i] != *i) {
+                        return Err(envman::EnvManError::IndexGap { key: key.clone(), index });
+                    }
+                    let found = indices.len();
+                    #count_check
+                    let mut errors = Vec::new();
+                    let mut items = Vec::with_capacity(found);
+                    for index in 0..found {
+                        let element = __context
+                            .element(format!("{}{}{}", context, index, separator), __separator);
+                        match envman::EnvMan::load_nested(__source, &element) {
+                            Ok(item) => items.push(item),
+                            Err(err) => errors.push(err.nested(&format!("{}.{}", #field, index))),
+                        }
+                    }
+                    if !errors.is_empty() {
+                        return Err(envman::EnvManError::from_errors(errors));
+                    }
+                    items
+                }
+            };
+            let is_present = quote! {
+                !__source.indices(&context, separator).is_empty()
+            };
+            (load_from, is_present)
+        } else if map {
+            let child = map_value_ty(&value_ty)?;
+            let count_check = count_check(min.as_ref(), max.as_ref());
+            let load_from = quote! {
+                {
+                    let names = __source.names(
+                        &context,
+                        separator,
+                        &__context.relative_keys::<#child>(__separator),
+                    );
+                    let found = names.len();
+                    #count_check
+                    let mut errors = Vec::new();
+                    let mut entries = Vec::with_capacity(found);
+                    for name in names {
+                        let element = __context
+                            .element(format!("{}{}{}", context, name, separator), __separator);
+                        // Errors name the entry by its key in the map
+                        let entry_key = <#child as envman::EnvMan>::map_key(&name);
+                        match <#child as envman::EnvMan>::load_nested(__source, &element) {
+                            Ok(item) => entries.push((entry_key, item)),
+                            Err(err) => errors.push(err.nested(&format!("{}.{}", #field, entry_key))),
+                        }
+                    }
+                    if !errors.is_empty() {
+                        return Err(envman::EnvManError::from_errors(errors));
+                    }
+                    entries.into_iter().collect()
+                }
+            };
+            let is_present = quote! {
+                !__source
+                    .names(&context, separator, &__context.relative_keys::<#child>(__separator))
+                    .is_empty()
+            };
+            (load_from, is_present)
+        } else {
+            let load_from = quote! {
+                envman::EnvMan::load_nested(__source, &context).map_err(|err| err.nested(#field))?
+            };
+            let is_present = quote! {
+                <#value_ty as envman::EnvMan>::is_present(__source, &context)
+            };
+            (load_from, is_present)
         };
 
+        // `Option` and `default` only apply when none of the nested variables are set,
+        // so that errors in a partially set struct are not hidden.
         let token = if is_option {
-            if default.is_some() {
-                quote! {
-                    #load_from_env.ok().or_else(|| Some(#default))
+            let absent = match default {
+                Some(default) => quote! { Some(#default) },
+                None => quote! { None },
+            };
+            quote! {
+                if #is_present {
+                    Some(#load_from)
+                } else {
+                    #absent
                 }
-            } else {
-                quote! {
-                    #load_from_env.ok()
+            }
+        } else if let Some(default) = default {
+            quote! {
+                if #is_present {
+                    #load_from
+                } else {
+                    #default
                 }
             }
-        } else if default.is_some() {
+        } else {
+            load_from
+        };
+        let token = if indexed || map {
+            let key = key(&name, renamed);
             quote! {
-                #load_from_env.unwrap_or_else(|_| #default)
+                {
+                    let key: String = #key;
+                    let separator = __separator.unwrap_or("_");
+                    let context = format!("{}{}", key, separator);
+                    #token
+                }
             }
         } else {
+            let nest_context = nest_context(&segment, nest_prefix.as_deref());
             quote! {
-                #load_from_env?
+                {
+                    let context = #nest_context;
+                    #token
+                }
             }
         };
 
@@ -60,56 +264,370 @@ pub(crate) fn derive(args: EnvManFieldArgs) -> syn::Result<proc_macro2::TokenStr
         };
     }
 
+    if nest_prefix.is_some() {
+        return Err(syn::Error::new(
+            span,
+            "`prefix` is only allowed when `nest` is true",
+        ));
+    }
+    if indexed || map || min.is_some() || max.is_some() {
+        return Err(syn::Error::new(
+            span,
+            "`indexed`, `map`, `min` and `max` are only allowed when `nest` is true",
+        ));
+    }
+
+    if separator.is_none()
+        && (validate_each.is_some()
+            || unique
+            || sorted
+            || min_items.is_some()
+            || max_items.is_some())
+    {
+        return Err(syn::Error::new(
+            span,
+            "`validate_each`, `unique`, `sorted`, `min_items` and `max_items` are only allowed with `separator`",
+        ));
+    }
+
+    if list_syntax_set && separator.is_none() {
+        return Err(syn::Error::new(
+            span,
+            "`list_syntax` is only allowed with `separator`",
+        ));
+    }
+
+    let element_separators = [&kv_separator, &tuple_separator, &inner_separator]
+        .into_iter()
+        .filter(|v| v.is_some())
+        .count();
+    if element_separators > 0 {
+        if separator.is_none() {
+            return Err(syn::Error::new(
+                span,
+                "`kv_separator`, `tuple_separator` and `inner_separator` are only allowed with `separator`",
+            ));
+        }
+        if element_separators > 1 {
+            return Err(syn::Error::new(
+                span,
+                "only one of `kv_separator`, `tuple_separator` and `inner_separator` can be used",
+            ));
+        }
+        if parser.is_some() {
+            return Err(syn::Error::new_spanned(
+                parser,
+                "`parser` is not allowed with `kv_separator`, `tuple_separator` or `inner_separator`",
+            ));
+        }
+    }
+    if kv_separator.is_some() && (validate_each.is_some() || unique || sorted) {
+        return Err(syn::Error::new(
+            span,
+            "`validate_each`, `unique` and `sorted` are not allowed with `kv_separator`",
+        ));
+    }
+
+    if os {
+        if parser.is_some() {
+            return Err(syn::Error::new_spanned(
+                parser,
+                "`parser` is not allowed when `os` is true",
+            ));
+        }
+        if separator.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`separator` is not allowed when `os` is true",
+            ));
+        }
+    }
+
     let parser = match parser {
         Some(parser) => quote! { #parser },
         None => quote! { std::str::FromStr::from_str },
     };
 
+    // The raw value as a `String`, for error messages
+    let raw_value = if os {
+        quote! { val.to_string_lossy().into_owned() }
+    } else {
+        quote! { val.clone() }
+    };
+
     // Handle separator (for Vec/array types)
-    let parse_with_separator = if let Some(sep) = separator {
+    let count_check = if min_items.is_some() || max_items.is_some() {
+        let count_check = count_check(min_items.as_ref(), max_items.as_ref());
+        quote! {
+            let found = results.len();
+            #count_check
+        }
+    } else {
+        quote! {}
+    };
+
+    // Parses `text`, a part of element `index` at `position`, with `FromStr`
+    let parse_part = |text: proc_macro2::TokenStream,
+                      ty: &syn::Type,
+                      position: proc_macro2::TokenStream| {
         quote! {
             {
-                let parts: Vec<&str> = val.split(#sep).collect();
-                let mut results = Vec::new();
-                for part in parts {
-                    let parsed = #parser(part.trim()).map_err(|err| envman::EnvManError::Parse {
-                        key: #name,
+                let text = #text;
+                <#ty as std::str::FromStr>::from_str(text.trim()).map_err(|err| envman::EnvManError::Parse {
+                    key: key.clone(),
+                    value: text.to_string(),
+                    expected_type: std::any::type_name::<#value_ty>(),
+                    field: #field,
+                    struct_name: #struct_name,
+                    element: Some(Box::new(envman::ParseElement {
+                        index,
+                        position: #position,
+                        expected_type: std::any::type_name::<#ty>(),
+                    })),
+                    source: Box::new(err)
+                })?
+            }
+        }
+    };
+
+    // Quoted items keep their whitespace, which plain items lose
+    let split_list = match list_syntax {
+        ListSyntax::Json => quote! { envman::split_json_list },
+        _ => quote! { envman::split_list },
+    };
+    let (split, trim) = match &separator {
+        Some(sep) if list_syntax != ListSyntax::Plain => (
+            quote! {
+                let parts: Vec<String> = #split_list(&val, #sep).map_err(|message| {
+                    envman::EnvManError::ListSyntax {
+                        key: key.clone(),
+                        value: val.clone(),
+                        message,
+                    }
+                })?;
+                let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+            },
+            quote! {},
+        ),
+        Some(sep) => (
+            quote! { let parts: Vec<&str> = val.split(#sep).collect(); },
+            quote! { .trim() },
+        ),
+        None => (quote! {}, quote! {}),
+    };
+
+    let parse_with_separator = if os {
+        quote! {
+            std::convert::From::<std::ffi::OsString>::from(val.clone())
+        }
+    } else if let (Some(_), Some(kv_sep)) = (&separator, &kv_separator) {
+        let (key_ty, value_ty_of_map) = map_entry_ty(&value_ty).ok_or_else(|| {
+            syn::Error::new_spanned(
+                &value_ty,
+                "`kv_separator` requires a map type such as `HashMap<K, V>`",
+            )
+        })?;
+        // Each pair is split at the first `kv_separator`, the key is position 0 and the value position 1
+        let parse_key = parse_part(quote! { entry_key }, key_ty, quote! { Some(0) });
+        let parse_value = parse_part(quote! { entry_value }, value_ty_of_map, quote! { Some(1) });
+        quote! {
+            {
+                #split
+                let mut results: Vec<(#key_ty, #value_ty_of_map)> = Vec::new();
+                for (index, part) in parts.into_iter().enumerate() {
+                    let Some((entry_key, entry_value)) = part.split_once(#kv_sep) else {
+                        return Err(envman::EnvManError::MalformedPair {
+                            key: key.clone(),
+                            index,
+                            value: part.to_string(),
+                            kv_separator: #kv_sep,
+                        });
+                    };
+                    let parsed_key = #parse_key;
+                    if results.iter().any(|(existing, _)| *existing == parsed_key) {
+                        return Err(envman::EnvManError::DuplicateKey {
+                            key: key.clone(),
+                            index,
+                            entry_key: entry_key.trim().to_string(),
+                        });
+                    }
+                    let parsed_value = #parse_value;
+                    results.push((parsed_key, parsed_value));
+                }
+                #count_check
+                <#value_ty as std::iter::FromIterator<(#key_ty, #value_ty_of_map)>>::from_iter(results)
+            }
+        }
+    } else if separator.is_some() {
+        // Checks of each element against itself and the elements before it
+        let element_error = quote! {
+            |message: String| envman::EnvManError::ElementValidation {
+                key: key.clone(),
+                index,
+                value: part.to_string(),
+                message,
+            }
+        };
+        let validate_each = validate_each.map(|validator| {
+            quote! {
+                if let Err(e) = #validator(&parsed) {
+                    return Err((#element_error)(format!("{}", e)));
+                }
+            }
+        });
+        let unique = unique.then(|| {
+            quote! {
+                if let Some(first) = results.iter().position(|element| *element == parsed) {
+                    return Err((#element_error)(format!("duplicate of element {}", first)));
+                }
+            }
+        });
+        let sorted = sorted.then(|| {
+            quote! {
+                if results.last().is_some_and(|last| parsed < *last) {
+                    return Err((#element_error)(format!(
+                        "must not be less than element {}",
+                        index - 1
+                    )));
+                }
+            }
+        });
+        // The elements are collected into a `Vec` first, then into the type of the field
+        let (results, collect) = match element_ty(&value_ty) {
+            Some(ElementTy::Array(elem, len)) => (
+                quote! { let mut results: Vec<#elem> = Vec::new(); },
+                quote! {
+                    let found = results.len();
+                    <[#elem; #len]>::try_from(results).map_err(|_| envman::EnvManError::Count {
+                        key: key.clone(),
+                        found,
+                        min: Some(#len),
+                        max: Some(#len),
+                    })?
+                },
+            ),
+            Some(ElementTy::Collection(elem)) => (
+                quote! { let mut results: Vec<#elem> = Vec::new(); },
+                quote! { <#value_ty as std::iter::FromIterator<#elem>>::from_iter(results) },
+            ),
+            // Left to inference, which only works for `Vec`
+            None => (quote! { let mut results = Vec::new(); }, quote! { results }),
+        };
+        let element = match element_ty(&value_ty) {
+            Some(ElementTy::Array(elem, _)) | Some(ElementTy::Collection(elem)) => Some(elem),
+            None => None,
+        };
+        let parse_element = if let Some(tuple_sep) = &tuple_separator {
+            let Some(syn::Type::Tuple(tuple)) = element else {
+                return Err(syn::Error::new_spanned(
+                    &value_ty,
+                    "`tuple_separator` requires a collection of tuples such as `Vec<(String, u16)>`",
+                ));
+            };
+            let expected = tuple.elems.len();
+            let positions = tuple.elems.iter().enumerate().map(|(position, ty)| {
+                parse_part(quote! { pieces[#position] }, ty, quote! { Some(#position) })
+            });
+            quote! {
+                let pieces: Vec<&str> = part.split(#tuple_sep).collect();
+                if pieces.len() != #expected {
+                    return Err(envman::EnvManError::TupleArity {
+                        key: key.clone(),
+                        index,
                         value: part.to_string(),
-                        expected_type: std::any::type_name::<Self>(),
-                        source: Box::new(err)
-                    })?;
+                        tuple_separator: #tuple_sep,
+                        found: pieces.len(),
+                        expected: #expected,
+                    });
+                }
+                let parsed = ( #( #positions, )* );
+            }
+        } else if let Some(inner_sep) = &inner_separator {
+            let Some((inner, Some(ElementTy::Collection(inner_elem)))) =
+                element.map(|elem| (elem, element_ty(elem)))
+            else {
+                return Err(syn::Error::new_spanned(
+                    &value_ty,
+                    "`inner_separator` requires a collection of collections such as `Vec<Vec<u16>>`",
+                ));
+            };
+            let parse_piece = parse_part(quote! { piece }, inner_elem, quote! { Some(position) });
+            quote! {
+                let mut pieces: Vec<#inner_elem> = Vec::new();
+                for (position, piece) in part.split(#inner_sep).enumerate() {
+                    pieces.push(#parse_piece);
+                }
+                let parsed = <#inner as std::iter::FromIterator<#inner_elem>>::from_iter(pieces);
+            }
+        } else {
+            quote! {
+                let parsed = #parser(part #trim);
+                let element_type = type_name_of_ok(&parsed);
+                let parsed = parsed.map_err(|err| envman::EnvManError::Parse {
+                    key: key.clone(),
+                    value: part.to_string(),
+                    expected_type: std::any::type_name::<#value_ty>(),
+                    field: #field,
+                    struct_name: #struct_name,
+                    element: Some(Box::new(envman::ParseElement {
+                        index,
+                        position: None,
+                        expected_type: element_type,
+                    })),
+                    source: Box::new(err)
+                })?;
+            }
+        };
+        quote! {
+            {
+                fn type_name_of_ok<T, E>(_: &Result<T, E>) -> &'static str {
+                    std::any::type_name::<T>()
+                }
+
+                #split
+                #results
+                for (index, part) in parts.into_iter().enumerate() {
+                    #parse_element
+                    #validate_each
+                    #unique
+                    #sorted
                     results.push(parsed);
                 }
-                results
+                #count_check
+                #collect
             }
         }
     } else {
         quote! {
             #parser(&val).map_err(|err| envman::EnvManError::Parse {
-                key: #name,
+                key: key.clone(),
                 value: val.clone(),
-                expected_type: std::any::type_name::<Self>(),
+                expected_type: std::any::type_name::<#value_ty>(),
+                field: #field,
+                struct_name: #struct_name,
+                element: None,
                 source: Box::new(err)
             })?
         }
     };
 
-    // Handle validation
-    let validation_code = if let Some(validator) = validate {
-        quote! {
-            match #validator(&parsed_value) {
+    // Handle validation, the built-in validators run before `validate`
+    let validators = validators
+        .into_iter()
+        .chain(validate.map(|validator| quote! { #validator(&parsed_value) }));
+    let validation_code = quote! {
+        #(
+            match #validators {
                 Ok(_) => {},
                 Err(e) => {
                     return Err(envman::EnvManError::Validation {
-                        key: #name,
-                        value: val.clone(),
+                        key: key.clone(),
+                        value: #raw_value,
                         message: format!("{}", e),
                     });
                 }
             }
-        }
-    } else {
-        quote! {}
+        )*
     };
 
     let ok = if is_option {
@@ -130,12 +648,22 @@ pub(crate) fn derive(args: EnvManFieldArgs) -> syn::Result<proc_macro2::TokenStr
         }
     };
 
+    // Turns a string literal given to `default` or `test` into the raw value
+    let literal_value = |expr: &proc_macro2::TokenStream| {
+        if os {
+            quote! { std::ffi::OsString::from(#expr) }
+        } else {
+            quote! { #expr.to_string() }
+        }
+    };
+
     let default_value = match default {
         Some(ref default_expr) => {
+            let default_literal = literal_value(default_expr);
             let parsed_default = if alltime_parse {
                 quote! {
                     {
-                        let val = #default_expr.to_string();
+                        let val = #default_literal;
                         let parsed_value = #parse_with_separator;
                         #validation_code
                         parsed_value
@@ -155,24 +683,136 @@ pub(crate) fn derive(args: EnvManFieldArgs) -> syn::Result<proc_macro2::TokenStr
             if is_option {
                 quote! { None }
             } else {
-                quote! { return Err(envman::EnvManError::NotFound { key: #name }) }
+                quote! { return Err(envman::EnvManError::NotFound { key: key.clone() }) }
             }
         }
     };
 
+    // An empty value is loaded like a missing one, rejected, or parsed
+    let on_empty = match empty {
+        EmptyPolicy::Unset => Some(default_value.clone()),
+        EmptyPolicy::Error => Some(quote! {
+            return Err(envman::EnvManError::Empty { key: key.clone() })
+        }),
+        EmptyPolicy::Value => None,
+    };
+    let token = if os {
+        let on_empty =
+            on_empty.map(|on_empty| quote! { Some(val) if val.is_empty() => #on_empty, });
+        quote! {
+            match __source.var_os(&key) {
+                #on_empty
+                Some(val) => #ok,
+                None => #default_value,
+            }
+        }
+    } else {
+        let on_empty = on_empty.map(|on_empty| quote! { Ok(val) if val.is_empty() => #on_empty, });
+        let trim = trim_value.then(|| quote! { .map(|val| val.trim().to_string()) });
+        quote! {
+            match __source.var(&key)#trim {
+                #on_empty
+                Ok(val) => #ok,
+                Err(std::env::VarError::NotPresent) => #default_value,
+                Err(std::env::VarError::NotUnicode(_)) => {
+                    return Err(envman::EnvManError::NotUnicode { key: key.clone() })
+                }
+            }
+        }
+    };
+
+    // Conditions on other variables are checked before the field is loaded. A variable named like a
+    // field, by its `rename` or its name, is read with the key, `empty` and `trim` of the field.
+    // Other variables get the prefix and suffix of the struct.
+    let suffix = struct_arg.suffix.as_deref().unwrap_or_default();
+    let resolve = |other: &str| match siblings.iter().find(|sibling| {
+        sibling.segment == other || sibling.field == other || sibling.cased == other
+    }) {
+        Some(sibling) => (sibling.key.clone(), sibling.empty, sibling.trim),
+        None => (
+            quote! { format!("{}{}{}", __prefix, #other, #suffix) },
+            struct_arg.empty,
+            struct_arg.trim,
+        ),
+    };
+    let key_is_set = is_set(quote! { key }, empty, trim_value);
+    let required_if = required_if.iter().map(|(other, value)| {
+        let (other, _, trim) = resolve(other);
+        let trim = trim.then(|| quote! { .trim() });
+        quote! {
+            {
+                let other: String = #other;
+                if !#key_is_set && __source.var(&other).is_ok_and(|val| val #trim == #value)
+                {
+                    return Err(envman::EnvManError::Required {
+                        key: key.clone(),
+                        condition: format!("when '{}' is '{}'", other, #value),
+                    });
+                }
+            }
+        }
+    });
+    let required_unless = if required_unless.is_empty() {
+        quote! {}
+    } else {
+        let (others, others_set): (Vec<_>, Vec<_>) = required_unless
+            .iter()
+            .enumerate()
+            .map(|(i, other)| {
+                let (other, empty, trim) = resolve(other);
+                (other, is_set(quote! { others[#i] }, empty, trim))
+            })
+            .unzip();
+        quote! {
+            {
+                let others: Vec<String> = vec![#( #others ),*];
+                if !#key_is_set #( && !#others_set )* {
+                    return Err(envman::EnvManError::Required {
+                        key: key.clone(),
+                        condition: format!(
+                            "unless {} is set",
+                            others
+                                .iter()
+                                .map(|other| format!("'{}'", other))
+                                .collect::<Vec<_>>()
+                                .join(" or ")
+                        ),
+                    });
+                }
+            }
+        }
+    };
+    let conflicts_with = conflicts_with.iter().map(|other| {
+        let (other, other_empty, other_trim) = resolve(other);
+        let other_is_set = is_set(quote! { other }, other_empty, other_trim);
+        quote! {
+            {
+                let other: String = #other;
+                if #key_is_set && #other_is_set {
+                    return Err(envman::EnvManError::Conflict {
+                        key: key.clone(),
+                        other,
+                    });
+                }
+            }
+        }
+    });
     let token = quote! {
-        match std::env::var(#name) {
-            Ok(val) => #ok,
-            Err(_) => #default_value,
+        {
+            #( #required_if )*
+            #required_unless
+            #( #conflicts_with )*
+            #token
         }
     };
 
-    match test {
+    let token = match test {
         Some(ref test_expr) => {
+            let test_literal = literal_value(test_expr);
             let parsed_test = if alltime_parse {
                 quote! {
                     {
-                        let val = #test_expr.to_string();
+                        let val = #test_literal;
                         let parsed_value = #parse_with_separator;
                         #validation_code
                         parsed_value
@@ -188,14 +828,242 @@ pub(crate) fn derive(args: EnvManFieldArgs) -> syn::Result<proc_macro2::TokenStr
                 parsed_test
             };
 
-            Ok(quote! {
+            quote! {
                 if cfg!(test) {
                     #test_value
                 } else {
                     #token
                 }
-            })
+            }
+        }
+        None => token,
+    };
+
+    Ok(quote! {
+        {
+            let key: String = #resolved_key;
+            #token
+        }
+    })
+}
+
+/// Expression of the key the field is read from, the first of its key and `alias` keys that is set,
+/// or else its key
+pub(crate) fn resolved_key(args: &EnvManFieldArgs) -> proc_macro2::TokenStream {
+    let key = key(&args.name, args.renamed);
+    if args.aliases.is_empty() {
+        return key;
+    }
+    let aliases = &args.aliases;
+    let key_is_set = is_set(quote! { key }, args.empty, args.trim);
+    let alias_is_set = is_set(quote! { alias }, args.empty, args.trim);
+    quote! {
+        {
+            let key: String = #key;
+            if #key_is_set {
+                key
+            } else {
+                [#( #aliases ),*]
+                    .into_iter()
+                    .find(|alias| #alias_is_set)
+                    .map_or(key, String::from)
+            }
+        }
+    }
+}
+
+/// Expression checking whether the variables of the field are set
+pub(crate) fn presence(args: &EnvManFieldArgs) -> proc_macro2::TokenStream {
+    let EnvManFieldArgs {
+        name,
+        renamed,
+        segment,
+        value_ty,
+        nest,
+        nest_prefix,
+        indexed,
+        map,
+        empty,
+        trim,
+        ..
+    } = args;
+
+    if *nest && (*indexed || *map) {
+        let key = key(name, *renamed);
+        let found = if *indexed {
+            quote! { __source.indices(&context, separator) }
+        } else {
+            let Ok(child) = map_value_ty(value_ty) else {
+                return quote! { false };
+            };
+            quote! {
+                __source.names(&context, separator, &__context.relative_keys::<#child>(__separator))
+            }
+        };
+        quote! {
+            {
+                let separator = __separator.unwrap_or("_");
+                let context = format!("{}{}", #key, separator);
+                !#found.is_empty()
+            }
+        }
+    } else if *nest {
+        let nest_context = nest_context(segment, nest_prefix.as_deref());
+        quote! {
+            <#value_ty as envman::EnvMan>::is_present(__source, &#nest_context)
+        }
+    } else {
+        let key = key(name, *renamed);
+        let is_set = std::iter::once(key)
+            .chain(args.aliases.iter().map(|alias| quote! { #alias }))
+            .map(|key| is_set(key, *empty, *trim));
+        quote! { (false #( || #is_set )*) }
+    }
+}
+
+/// Expression checking whether the variable `key` is set, ignoring empty values if they mean unset
+pub(crate) fn is_set(
+    key: proc_macro2::TokenStream,
+    empty: EmptyPolicy,
+    trim: bool,
+) -> proc_macro2::TokenStream {
+    if empty != EmptyPolicy::Unset {
+        return quote! { __source.var_os(&#key).is_some() };
+    }
+    let trim = trim.then(|| quote! { .trim() });
+    quote! {
+        __source
+            .var_os(&#key)
+            .is_some_and(|val| !val.to_string_lossy()#trim.is_empty())
+    }
+}
+
+pub(crate) fn key(name: &str, renamed: bool) -> proc_macro2::TokenStream {
+    if renamed {
+        quote! { String::from(#name) }
+    } else {
+        quote! { format!("{}{}", __prefix, #name) }
+    }
+}
+
+fn nest_context(segment: &str, nest_prefix: Option<&str>) -> proc_macro2::TokenStream {
+    let nest_prefix = match nest_prefix {
+        Some(prefix) => quote! { Some(#prefix) },
+        None => quote! { None },
+    };
+    quote! { __context.nest(&__prefix, __separator, #segment, #nest_prefix) }
+}
+
+/// Statement returning `EnvManError::Count` if `found` is out of `min..=max`
+fn count_check(
+    min: Option<&proc_macro2::TokenStream>,
+    max: Option<&proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    if min.is_none() && max.is_none() {
+        return quote! {};
+    }
+    let bound = |bound: Option<&proc_macro2::TokenStream>| match bound {
+        Some(bound) => quote! { Some(#bound) },
+        None => quote! { None },
+    };
+    let (min, max) = (bound(min), bound(max));
+    quote! {
+        let (min, max): (Option<usize>, Option<usize>) = (#min, #max);
+        if min.is_some_and(|min| found < min) || max.is_some_and(|max| found > max) {
+            return Err(envman::EnvManError::Count { key: key.clone(), found, min, max });
+        }
+    }
+}
+
+/// Keys read by the field, pushed to `__keys`
+pub(crate) fn key_names(args: &EnvManFieldArgs) -> proc_macro2::TokenStream {
+    let EnvManFieldArgs {
+        name,
+        renamed,
+        segment,
+        value_ty,
+        nest,
+        nest_prefix,
+        indexed,
+        map,
+        ..
+    } = args;
+
+    if *nest && (*indexed || *map) {
+        // The keys of the entries are only known from the source
+        quote! {}
+    } else if *nest {
+        let nest_context = nest_context(segment, nest_prefix.as_deref());
+        quote! {
+            __keys.extend(<#value_ty as envman::EnvMan>::key_names(&#nest_context));
+        }
+    } else {
+        let key = key(name, *renamed);
+        let aliases = &args.aliases;
+        quote! {
+            __keys.push(#key);
+            #( __keys.push(String::from(#aliases)); )*
+        }
+    }
+}
+
+enum ElementTy<'a> {
+    /// `[T; N]`
+    Array(&'a syn::Type, &'a syn::Expr),
+    /// `T` of a `FromIterator<T>` collection such as `HashSet<T>` or `Box<[T]>`
+    Collection(&'a syn::Type),
+}
+
+/// The type of the elements of a `separator` field
+fn element_ty(ty: &syn::Type) -> Option<ElementTy<'_>> {
+    match ty {
+        syn::Type::Array(array) => Some(ElementTy::Array(&array.elem, &array.len)),
+        syn::Type::Path(path) => {
+            let syn::PathArguments::AngleBracketed(args) = &path.path.segments.last()?.arguments
+            else {
+                return None;
+            };
+            match args.args.first()? {
+                syn::GenericArgument::Type(syn::Type::Slice(slice)) => {
+                    Some(ElementTy::Collection(&slice.elem))
+                }
+                syn::GenericArgument::Type(ty) => Some(ElementTy::Collection(ty)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The `K` and `V` of `HashMap<K, V>` or `BTreeMap<K, V>`
+fn map_entry_ty(ty: &syn::Type) -> Option<(&syn::Type, &syn::Type)> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let syn::PathArguments::AngleBracketed(args) = &path.path.segments.last()?.arguments else {
+        return None;
+    };
+    match (args.args.first()?, args.args.iter().nth(1)?) {
+        (syn::GenericArgument::Type(key), syn::GenericArgument::Type(value)) => Some((key, value)),
+        _ => None,
+    }
+}
+
+/// The `T` of `HashMap<String, T>` or `BTreeMap<String, T>`
+fn map_value_ty(ty: &syn::Type) -> syn::Result<&syn::Type> {
+    if let syn::Type::Path(path) = ty {
+        if let Some(syn::PathSegment {
+            arguments: syn::PathArguments::AngleBracketed(args),
+            ..
+        }) = path.path.segments.last()
+        {
+            if let Some(syn::GenericArgument::Type(ty)) = args.args.iter().nth(1) {
+                return Ok(ty);
+            }
         }
-        None => Ok(token),
     }
+    Err(syn::Error::new_spanned(
+        ty,
+        "`map` requires a map type such as `HashMap<String, T>`",
+    ))
 }
@@ -0,0 +1,230 @@
+use convert_case::{Boundary, Casing};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{punctuated::Punctuated, spanned::Spanned, Meta, Token};
+
+use crate::{check_duplicate, require_lit_str, EmptyPolicy};
+
+struct EnvManVariantArgs {
+    /// Value of the tag selecting the variant
+    name: String,
+    default: bool,
+}
+
+/// Parse the #[envman(...)] attributes of a variant.
+fn variant_attr(
+    variant: &syn::Variant,
+    rename_all: convert_case::Case<'static>,
+) -> syn::Result<EnvManVariantArgs> {
+    let mut rename: Option<String> = None;
+    let mut default = false;
+
+    for attr in &variant.attrs {
+        if !attr.path().is_ident("envman") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            match meta {
+                Meta::NameValue(meta) if meta.path.is_ident("rename") => {
+                    check_duplicate!(meta.span(), rename);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    rename = Some(string);
+                }
+                Meta::Path(ref path) if path.is_ident("default") => {
+                    check_duplicate!(path.span(), default, default);
+                    default = true;
+                }
+                _ => return Err(syn::Error::new_spanned(meta, "unexpected attribute")),
+            }
+        }
+    }
+
+    Ok(EnvManVariantArgs {
+        name: rename.unwrap_or_else(|| variant_name(variant, rename_all)),
+        default,
+    })
+}
+
+/// Name of the variant converted with the `rename_all` rule
+pub(crate) fn variant_name(
+    variant: &syn::Variant,
+    rename_all: convert_case::Case<'static>,
+) -> String {
+    // Digits do not start a new word, so that `S3` stays `S3` instead of `S_3`
+    variant
+        .ident
+        .to_string()
+        .with_boundaries(&[Boundary::LOWER_UPPER, Boundary::ACRONYM])
+        .to_case(rename_all)
+}
+
+pub(crate) fn derive_enum(
+    input: &syn::DeriveInput,
+    data: &syn::DataEnum,
+) -> syn::Result<TokenStream> {
+    let attr_arg = crate::struct_attr::struct_attr(input)?;
+    if attr_arg.validate_struct.is_some() || !attr_arg.groups.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "`validate_struct` and `group` are only allowed on structs",
+        ));
+    }
+    if attr_arg.key.is_some() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "`key` is only allowed on tuple structs",
+        ));
+    }
+    let tag = attr_arg.tag.as_deref().ok_or_else(|| {
+        syn::Error::new_spanned(
+            input,
+            "enums require a `tag` attribute naming the variable that selects the variant",
+        )
+    })?;
+
+    let mut names = Vec::new();
+    let mut arms = Vec::new();
+    let mut default = None;
+    for variant in &data.variants {
+        let args = variant_attr(variant, attr_arg.rename_all)?;
+        let ident = &variant.ident;
+        let load = match &variant.fields {
+            syn::Fields::Unit => quote! { Self::#ident },
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                Self::#ident(envman::EnvMan::load_nested(__source, __context)?)
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "only unit variants and variants with a single unnamed field are supported",
+                ))
+            }
+        };
+
+        if args.default {
+            check_duplicate!(variant.span(), default);
+            default = Some(load.clone());
+        }
+
+        let name = args.name;
+        arms.push(quote! {
+            if value.eq_ignore_ascii_case(#name) {
+                return Ok(#load);
+            }
+        });
+        names.push(name);
+    }
+
+    let not_present = match default {
+        Some(load) => quote! { return Ok(#load) },
+        None => quote! { return Err(envman::EnvManError::NotFound { key }) },
+    };
+
+    // `empty` and `trim` of the struct apply to the tag
+    let on_empty = match attr_arg.empty {
+        EmptyPolicy::Unset => quote! { Ok(value) if value.is_empty() => #not_present, },
+        EmptyPolicy::Error => quote! {
+            Ok(value) if value.is_empty() => {
+                return Err(envman::EnvManError::Empty { key })
+            }
+        },
+        EmptyPolicy::Value => quote! {},
+    };
+    let trim = attr_arg
+        .trim
+        .then(|| quote! { .map(|value| value.trim().to_string()) });
+    let is_present = match attr_arg.empty {
+        EmptyPolicy::Unset => {
+            let trim = attr_arg.trim.then(|| quote! { .trim() });
+            quote! {
+            __source
+                .var_os(&format!("{}{}", __prefix, #tag))
+                .is_some_and(|value| !value.to_string_lossy()#trim.is_empty())
+            }
+        }
+        _ => quote! { __source.var_os(&format!("{}{}", __prefix, #tag)).is_some() },
+    };
+
+    let load = quote! {
+        let key = format!("{}{}", __prefix, #tag);
+        let value = match __source.var(&key)#trim {
+            #on_empty
+            Ok(value) => value,
+            Err(std::env::VarError::NotPresent) => #not_present,
+            Err(std::env::VarError::NotUnicode(_)) => {
+                return Err(envman::EnvManError::NotUnicode { key })
+            }
+        };
+        #( #arms )*
+        Err(envman::EnvManError::UnknownVariant {
+            key,
+            value,
+            expected: &[#( #names ),*],
+        })
+    };
+    // With `case_insensitive`, the keys read with several cases are reported along with the result
+    let load = if attr_arg.case_insensitive {
+        quote! {
+            let __result = (|| -> Result<Self, envman::EnvManError> { #load })();
+            let mut __errors = __source.take_ambiguities();
+            match __result {
+                Ok(value) if __errors.is_empty() => Ok(value),
+                Ok(_) => Err(envman::EnvManError::from_errors(__errors)),
+                Err(err) => {
+                    __errors.push(err);
+                    Err(envman::EnvManError::from_errors(__errors))
+                }
+            }
+        }
+    } else {
+        load
+    };
+
+    let resolve = crate::resolve_context(&attr_arg);
+    let case_insensitive = crate::case_insensitive(&attr_arg);
+    let map_key = crate::map_key(attr_arg.rename_all);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics envman::EnvMan for #ident #ty_generics #where_clause {
+            fn load_from<__S: envman::EnvSource + ?Sized>(
+                __source: &__S,
+            ) -> Result<Self, envman::EnvManError> {
+                Self::load_nested(__source, &envman::NestContext::default())
+            }
+
+            fn load_nested<__S: envman::EnvSource + ?Sized>(
+                __source: &__S,
+                __context: &envman::NestContext,
+            ) -> Result<Self, envman::EnvManError> {
+                #resolve
+                #case_insensitive
+                #load
+            }
+
+            fn is_present<__S: envman::EnvSource + ?Sized>(
+                __source: &__S,
+                __context: &envman::NestContext,
+            ) -> bool {
+                #resolve
+                #case_insensitive
+                #is_present
+            }
+
+            fn key_names(__context: &envman::NestContext) -> Vec<String> {
+                #resolve
+                vec![format!("{}{}", __prefix, #tag)]
+            }
+
+            fn map_key(name: &str) -> String {
+                #map_key
+            }
+        }
+    })
+}
@@ -0,0 +1,129 @@
+use convert_case::Case;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{punctuated::Punctuated, spanned::Spanned, Meta, Token};
+
+use crate::{check_duplicate, require_lit_str, struct_attr::from_str_to_case};
+
+/// Parse the #[envman(rename_all = "...")] attribute of the enum.
+fn enum_attr(input: &syn::DeriveInput) -> syn::Result<Case<'static>> {
+    let mut rename_all: Option<Case> = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("envman") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            match meta {
+                Meta::NameValue(meta) if meta.path.is_ident("rename_all") => {
+                    check_duplicate!(meta.span(), rename_all);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    rename_all = Some(
+                        from_str_to_case(&string)
+                            .ok_or_else(|| syn::Error::new(meta.span(), "invalid rename_all"))?,
+                    );
+                }
+                _ => return Err(syn::Error::new_spanned(meta, "unexpected attribute")),
+            }
+        }
+    }
+
+    Ok(rename_all.unwrap_or(Case::UpperSnake))
+}
+
+/// Parse the #[envman(rename = "...", alias = "...")] attributes of a variant into its accepted values.
+fn variant_attr(variant: &syn::Variant, rename_all: Case<'static>) -> syn::Result<Vec<String>> {
+    let mut rename: Option<String> = None;
+    let mut aliases = Vec::new();
+
+    for attr in &variant.attrs {
+        if !attr.path().is_ident("envman") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            match meta {
+                Meta::NameValue(meta) if meta.path.is_ident("rename") => {
+                    check_duplicate!(meta.span(), rename);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    rename = Some(string);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("alias") => {
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    aliases.push(string);
+                }
+                _ => return Err(syn::Error::new_spanned(meta, "unexpected attribute")),
+            }
+        }
+    }
+
+    let name = rename.unwrap_or_else(|| crate::enum_derive::variant_name(variant, rename_all));
+    aliases.insert(0, name);
+    Ok(aliases)
+}
+
+pub(crate) fn derive_from_str(input: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let syn::Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "EnvManEnum can only be derived for enums",
+        ));
+    };
+    let rename_all = enum_attr(input)?;
+
+    let mut expected: Vec<String> = Vec::new();
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "EnvManEnum only supports unit variants",
+            ));
+        }
+
+        let names = variant_attr(variant, rename_all)?;
+        for name in &names {
+            if expected.iter().any(|v| v.eq_ignore_ascii_case(name)) {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    format!("duplicate value `{name}`, values are matched case-insensitively"),
+                ));
+            }
+        }
+
+        let ident = &variant.ident;
+        arms.push(quote! {
+            if #( value.eq_ignore_ascii_case(#names) )||* {
+                return Ok(Self::#ident);
+            }
+        });
+        expected.extend(names);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics std::str::FromStr for #ident #ty_generics #where_clause {
+            type Err = envman::ParseEnumError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                #( #arms )*
+                Err(envman::ParseEnumError {
+                    value: value.to_string(),
+                    expected: &[#( #expected ),*],
+                })
+            }
+        }
+    })
+}
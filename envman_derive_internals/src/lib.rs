@@ -12,12 +12,59 @@ use proc_macro2::TokenStream;
 mod attr;
 mod debug_derive;
 mod derive;
+mod enum_derive;
+mod from_str_derive;
 mod struct_attr;
 
 struct EnvManStructArgs {
+    pub name: String,
     pub rename_all: Case<'static>,
     pub prefix: Option<String>,
     pub suffix: Option<String>,
+    pub inherit_prefix: bool,
+    pub nesting_separator: Option<String>,
+    pub tag: Option<String>,
+    /// Key of the field of a newtype struct
+    pub key: Option<String>,
+    pub validate_struct: Option<TokenStream>,
+    pub groups: Vec<EnvManGroup>,
+    /// Default of `list_syntax` for the fields
+    pub list_syntax: ListSyntax,
+    /// Default of `empty` for the fields
+    pub empty: EmptyPolicy,
+    /// Default of `trim` for the fields
+    pub trim: bool,
+    pub case_insensitive: bool,
+}
+
+/// How `separator` fields are split, set with `list_syntax = "..."`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ListSyntax {
+    /// Split at every separator and trim the elements
+    Plain,
+    /// Respect quotes and escapes with `envman::split_list`
+    Quoted,
+    /// Read JSON arrays, or else the quoted syntax, with `envman::split_json_list`
+    Json,
+}
+
+/// What an empty variable means, set with `empty = "..."`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EmptyPolicy {
+    /// The variable is treated as not set
+    Unset,
+    /// The variable is rejected with `EnvManError::Empty`
+    Error,
+    /// The empty string is parsed like any other value
+    Value,
+}
+
+/// A `group(name = "...", exactly_one = [...])` attribute
+struct EnvManGroup {
+    pub name: String,
+    /// Variant of `envman::GroupRule`
+    pub rule: syn::Ident,
+    pub members: Vec<syn::Ident>,
 }
 
 pub fn derive_envman(input: syn::DeriveInput) -> syn::Result<TokenStream> {
@@ -25,48 +72,363 @@ pub fn derive_envman(input: syn::DeriveInput) -> syn::Result<TokenStream> {
         syn::Data::Struct(syn::DataStruct {
             fields: syn::Fields::Named(fields),
             ..
-        }) => derive_envman_internal(&input, fields),
+        }) => derive_envman_internal(&input, &fields.named, true),
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Unnamed(fields),
+            ..
+        }) => derive_envman_internal(&input, &fields.unnamed, false),
+        syn::Data::Enum(data) => enum_derive::derive_enum(&input, data),
         _ => Err(syn::Error::new_spanned(
             input,
-            "currently only structs with named fields are supported",
+            "currently only structs with fields and enums are supported",
         )),
     }
 }
 
+pub fn derive_envman_enum(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    from_str_derive::derive_from_str(&input)
+}
+
 pub fn derive_envman_debug(input: syn::DeriveInput) -> syn::Result<TokenStream> {
     debug_derive::derive_debug(&input)
 }
 
 fn derive_envman_internal(
     input: &syn::DeriveInput,
-    fields: &syn::FieldsNamed,
+    fields: &Punctuated<syn::Field, syn::Token![,]>,
+    named: bool,
 ) -> syn::Result<TokenStream> {
     let attr_arg = struct_attr::struct_attr(input)?;
+    if attr_arg.tag.is_some() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "`tag` is only allowed on enums",
+        ));
+    }
+    if attr_arg.key.is_some() && (named || fields.len() != 1) {
+        return Err(syn::Error::new_spanned(
+            input,
+            "`key` is only allowed on tuple structs with a single field, put it on each field instead",
+        ));
+    }
+
+    // Fields of tuple structs are bound to `__field0`, `__field1`, ...
+    let field_name = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => ident.clone(),
+            None => quote::format_ident!("__field{}", i),
+        })
+        .collect::<Vec<_>>();
+    let field_ty = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+
+    let field_args = fields
+        .iter()
+        .enumerate()
+        .map(|(i, v)| attr::attr(v, i, &attr_arg))
+        .collect::<syn::Result<Vec<_>>>()?;
 
-    let field_name = fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    if attr_arg.case_insensitive {
+        check_case_collisions(&field_args)?;
+    }
+
+    let groups = attr_arg
+        .groups
+        .iter()
+        .map(|group| group_check(group, &field_args))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let presence = field_args.iter().map(derive::presence).collect::<Vec<_>>();
+    let key_names = field_args.iter().map(derive::key_names).collect::<Vec<_>>();
+    // Keys of the fields in errors, with the `alias` that was read instead of the key
+    let error_keys = field_args
+        .iter()
+        .zip(&key_names)
+        .map(|(args, key_names)| {
+            if args.nest {
+                key_names.clone()
+            } else {
+                let key = derive::resolved_key(args);
+                quote::quote! { __keys.push(#key); }
+            }
+        })
+        .collect::<Vec<_>>();
+    let field_str = field_args
+        .iter()
+        .map(|v| v.field.clone())
+        .collect::<Vec<_>>();
+    let map_key = map_key(attr_arg.rename_all);
 
-    let body = fields
-        .named
+    let siblings = field_args
         .iter()
-        .map(|v| attr::attr(v, &attr_arg))
-        .collect::<syn::Result<Vec<_>>>()?
+        .filter(|args| !args.nest)
+        .map(|args| derive::Sibling::new(args, attr_arg.rename_all))
+        .collect::<Vec<_>>();
+    let body = field_args
         .into_iter()
-        .map(derive::derive)
+        .map(|v| derive::derive(v, &attr_arg, &siblings))
         .collect::<syn::Result<Vec<_>>>()?;
 
     let ident = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
+    let value = if named {
+        quote::quote! { Self { #( #field_name, )* } }
+    } else {
+        quote::quote! { Self( #( #field_name, )* ) }
+    };
+    // The fields named by the validation are reported with their keys,
+    // or with their names if the keys are only known from the source
+    let value = match &attr_arg.validate_struct {
+        Some(validate_struct) => {
+            let struct_name = &attr_arg.name;
+            let (field_str, field_keys): (Vec<_>, Vec<_>) = field_str
+                .iter()
+                .zip(&error_keys)
+                .filter(|(_, keys)| !keys.is_empty())
+                .unzip();
+            quote::quote! {{
+                let __value = #value;
+                if let Err(err) = #validate_struct(&__value) {
+                    let err: envman::StructValidationError = err.into();
+                    let mut __keys = Vec::new();
+                    for field in &err.fields {
+                        match *field {
+                            #( #field_str => { #field_keys } )*
+                            other => __keys.push(other.to_string()),
+                        }
+                    }
+                    return Err(envman::EnvManError::StructValidation {
+                        struct_name: #struct_name,
+                        keys: __keys,
+                        message: err.message,
+                    });
+                }
+                __value
+            }}
+        }
+        None => value,
+    };
+
+    // Every field is loaded even if an earlier one failed, so that all errors are reported at once.
+    let construct = if field_name.is_empty() {
+        if let Some(group) = attr_arg.groups.first() {
+            return Err(syn::Error::new_spanned(
+                &group.rule,
+                "groups need fields as members",
+            ));
+        }
+        quote::quote! { Ok(#value) }
+    } else {
+        let case_check = case_check(&attr_arg);
+        quote::quote! {
+            let mut __errors: Vec<envman::EnvManError> = Vec::new();
+            #(
+                let #field_name = match (|| -> Result<#field_ty, envman::EnvManError> {
+                    let value = #body;
+                    Ok(value)
+                })() {
+                    Ok(value) => Some(value),
+                    Err(err) => {
+                        __errors.push(err);
+                        None
+                    }
+                };
+            )*
+            #( #groups )*
+            #case_check
+            match (#( #field_name, )*) {
+                (#( Some(#field_name), )*) if __errors.is_empty() => Ok(#value),
+                _ => Err(envman::EnvManError::from_errors(__errors)),
+            }
+        }
+    };
+
+    let resolve = resolve_context(&attr_arg);
+    let case_insensitive = case_insensitive(&attr_arg);
+
     let expr = quote::quote! {
         impl #impl_generics envman::EnvMan for #ident #ty_generics #where_clause {
-            fn load_from_env() -> Result<Self, envman::EnvManError> {
-                Ok(Self { #( #field_name: #body, )* })
+            fn load_from<__S: envman::EnvSource + ?Sized>(
+                __source: &__S,
+            ) -> Result<Self, envman::EnvManError> {
+                Self::load_nested(__source, &envman::NestContext::default())
+            }
+
+            fn load_nested<__S: envman::EnvSource + ?Sized>(
+                __source: &__S,
+                __context: &envman::NestContext,
+            ) -> Result<Self, envman::EnvManError> {
+                #resolve
+                #case_insensitive
+                #construct
+            }
+
+            fn is_present<__S: envman::EnvSource + ?Sized>(
+                __source: &__S,
+                __context: &envman::NestContext,
+            ) -> bool {
+                #resolve
+                #case_insensitive
+                false #( || #presence )*
+            }
+
+            fn key_names(__context: &envman::NestContext) -> Vec<String> {
+                #resolve
+                let mut __keys = Vec::new();
+                #( #key_names )*
+                __keys
+            }
+
+            fn map_key(name: &str) -> String {
+                #map_key
             }
         }
     };
     Ok(expr)
 }
 
+/// Pushes an error to `__errors` if the number of set members of the group breaks its rule
+fn group_check(
+    group: &EnvManGroup,
+    field_args: &[attr::EnvManFieldArgs],
+) -> syn::Result<TokenStream> {
+    let mut keys = Vec::new();
+    let mut is_set = Vec::new();
+    for (i, member) in group.members.iter().enumerate() {
+        let args = field_args
+            .iter()
+            .find(|args| member == &args.field)
+            .ok_or_else(|| syn::Error::new_spanned(member, "no field with this name"))?;
+        if args.nest {
+            return Err(syn::Error::new_spanned(
+                member,
+                "`nest` fields cannot be members of a group",
+            ));
+        }
+        if !args.is_option && args.default.is_none() {
+            return Err(syn::Error::new_spanned(
+                member,
+                "members of a group must be `Option` fields or fields with `default`",
+            ));
+        }
+        keys.push(derive::resolved_key(args));
+        is_set.push(derive::is_set(
+            quote::quote! { __keys[#i] },
+            args.empty,
+            args.trim,
+        ));
+    }
+
+    let name = &group.name;
+    let rule = &group.rule;
+    let satisfied = if rule == "ExactlyOne" {
+        quote::quote! { __set.len() == 1 }
+    } else if rule == "AtLeastOne" {
+        quote::quote! { !__set.is_empty() }
+    } else {
+        quote::quote! { __set.len() <= 1 }
+    };
+    Ok(quote::quote! {
+        {
+            let __keys: Vec<String> = vec![#( #keys ),*];
+            let __set: Vec<String> = [#( #is_set ),*]
+                .into_iter()
+                .zip(&__keys)
+                .filter(|(set, _)| *set)
+                .map(|(_, key)| key.clone())
+                .collect();
+            if !(#satisfied) {
+                __errors.push(envman::EnvManError::Group {
+                    group: #name,
+                    rule: envman::GroupRule::#rule,
+                    keys: __keys,
+                    set: __set,
+                });
+            }
+        }
+    })
+}
+
+/// Binds `__prefix` and `__separator` for the struct in `__context`
+fn resolve_context(attr_arg: &EnvManStructArgs) -> TokenStream {
+    let prefix = attr_arg.prefix.as_deref().unwrap_or_default();
+    let inherit_prefix = attr_arg.inherit_prefix;
+    let nesting_separator = match &attr_arg.nesting_separator {
+        Some(separator) => quote::quote! { Some(#separator) },
+        None => quote::quote! { None },
+    };
+    quote::quote! {
+        let __prefix = __context.resolve_prefix(#prefix, #inherit_prefix);
+        let __separator = __context.nesting_separator(#nesting_separator);
+    }
+}
+
+/// Statement replacing `__source` with a source matching keys ignoring case, with `case_insensitive`
+fn case_insensitive(attr_arg: &EnvManStructArgs) -> TokenStream {
+    if !attr_arg.case_insensitive {
+        return TokenStream::new();
+    }
+    quote::quote! {
+        // The source is no longer the generic `__S`, so its methods need the trait in scope
+        use envman::EnvSource as _;
+        let __source = &envman::CaseInsensitiveSource::new(__source);
+    }
+}
+
+/// Statement adding `EnvManError::AmbiguousKey` to `__errors` for the keys read with several cases
+fn case_check(attr_arg: &EnvManStructArgs) -> TokenStream {
+    if !attr_arg.case_insensitive {
+        return TokenStream::new();
+    }
+    quote::quote! {
+        __errors.extend(__source.take_ambiguities());
+    }
+}
+
+/// Reject fields whose keys only differ in case, which `case_insensitive` cannot tell apart
+fn check_case_collisions(field_args: &[attr::EnvManFieldArgs]) -> syn::Result<()> {
+    let mut seen: Vec<(bool, String, &str)> = Vec::new();
+    for args in field_args.iter().filter(|args| !args.nest) {
+        let lowercase = args.name.to_lowercase();
+        if let Some((_, _, other)) = seen
+            .iter()
+            .find(|(renamed, name, _)| *renamed == args.renamed && *name == lowercase)
+        {
+            return Err(syn::Error::new(
+                args.span,
+                format!(
+                    "the keys of `{}` and `{}` only differ in case, which `case_insensitive` cannot tell apart",
+                    other, args.field
+                ),
+            ));
+        }
+        seen.push((args.renamed, lowercase, &args.field));
+    }
+    Ok(())
+}
+
+/// Converts `name`, named with the `rename_all` rule, back to snake_case
+fn map_key(rename_all: Case<'static>) -> TokenStream {
+    if rename_all == Case::Kebab || rename_all == Case::UpperKebab {
+        quote::quote! { name.to_lowercase().replace('-', "_") }
+    } else if rename_all == Case::Pascal || rename_all == Case::Camel {
+        quote::quote! {
+            let mut key = String::with_capacity(name.len());
+            for (i, c) in name.char_indices() {
+                if c.is_uppercase() && i != 0 {
+                    key.push('_');
+                }
+                key.extend(c.to_lowercase());
+            }
+            key
+        }
+    } else {
+        quote::quote! { name.to_lowercase() }
+    }
+}
+
 macro_rules! check_duplicate {
     ($span:expr, $variant:ident) => {
         check_duplicate!(@__message $span, $variant, $variant.is_some(),);
@@ -88,7 +450,7 @@ macro_rules! check_duplicate {
 }
 
 pub(crate) use check_duplicate;
-use syn::spanned::Spanned;
+use syn::{punctuated::Punctuated, spanned::Spanned};
 
 fn require_lit_str<S: Spanned>(span: &S, expr: &syn::Expr) -> syn::Result<String> {
     if let syn::Expr::Lit(expr_lit) = &expr {
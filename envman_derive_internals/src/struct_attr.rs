@@ -1,13 +1,26 @@
-use crate::{check_duplicate, require_lit_str, EnvManStructArgs};
+use crate::{
+    check_duplicate, require_lit_str, EmptyPolicy, EnvManGroup, EnvManStructArgs, ListSyntax,
+};
 
 use convert_case::Case;
-use syn::{punctuated::Punctuated, spanned::Spanned, Meta, Token};
+use quote::ToTokens;
+use syn::{punctuated::Punctuated, spanned::Spanned, Expr, Meta, Token};
 
 /// Find the value of a #[envman(rename_all = "...")] attribute.
 pub(crate) fn struct_attr(derive: &syn::DeriveInput) -> syn::Result<EnvManStructArgs> {
     let mut rename_all: Option<Case> = None;
     let mut prefix: Option<String> = None;
     let mut suffix: Option<String> = None;
+    let mut inherit_prefix = false;
+    let mut nesting_separator: Option<String> = None;
+    let mut tag: Option<String> = None;
+    let mut key: Option<String> = None;
+    let mut validate_struct: Option<proc_macro2::TokenStream> = None;
+    let mut groups: Vec<EnvManGroup> = Vec::new();
+    let mut list_syntax: Option<ListSyntax> = None;
+    let mut empty: Option<EmptyPolicy> = None;
+    let mut trim: Option<bool> = None;
+    let mut case_insensitive = false;
 
     for attr in &derive.attrs {
         if !attr.path().is_ident("envman") {
@@ -42,14 +55,179 @@ pub(crate) fn struct_attr(derive: &syn::DeriveInput) -> syn::Result<EnvManStruct
 
                     suffix = Some(string);
                 }
+                Meta::Path(ref path) if path.is_ident("inherit_prefix") => {
+                    check_duplicate!(path.span(), inherit_prefix, inherit_prefix);
+                    inherit_prefix = true;
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("nesting_separator") => {
+                    check_duplicate!(meta.span(), nesting_separator);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    nesting_separator = Some(string);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("tag") => {
+                    check_duplicate!(meta.span(), tag);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    tag = Some(string);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("key") => {
+                    check_duplicate!(meta.span(), key);
+
+                    let string = require_lit_str(&meta, &meta.value)?;
+
+                    key = Some(string);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("validate_struct") => {
+                    check_duplicate!(meta.span(), validate_struct);
+
+                    if let Expr::Path(path) = &meta.value {
+                        validate_struct = Some(path.to_token_stream());
+                        continue;
+                    }
+
+                    return Err(syn::Error::new_spanned(meta, "expected path"));
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("list_syntax") => {
+                    check_duplicate!(meta.span(), list_syntax);
+
+                    list_syntax = Some(list_syntax_attr(&meta)?);
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("empty") => {
+                    check_duplicate!(meta.span(), empty);
+
+                    empty = Some(empty_policy(&meta)?);
+                }
+                Meta::Path(_) | Meta::NameValue(_) if meta.path().is_ident("trim") => {
+                    check_duplicate!(meta.span(), trim);
+
+                    trim = Some(trim_attr(&meta)?);
+                }
+                Meta::Path(ref path) if path.is_ident("case_insensitive") => {
+                    check_duplicate!(path.span(), case_insensitive, case_insensitive);
+                    case_insensitive = true;
+                }
+                Meta::List(ref list) if list.path.is_ident("group") => {
+                    groups.push(group(list)?);
+                }
                 _ => return Err(syn::Error::new_spanned(meta, "unexpected attribute")),
             }
         }
     }
     Ok(EnvManStructArgs {
+        name: derive.ident.to_string(),
         rename_all: rename_all.unwrap_or(Case::UpperSnake),
         prefix,
         suffix,
+        inherit_prefix,
+        nesting_separator,
+        tag,
+        key,
+        validate_struct,
+        groups,
+        list_syntax: list_syntax.unwrap_or(ListSyntax::Plain),
+        empty: empty.unwrap_or(EmptyPolicy::Value),
+        trim: trim.unwrap_or_default(),
+        case_insensitive,
+    })
+}
+
+/// Parse `list_syntax = "plain"`, `list_syntax = "quoted"` or `list_syntax = "json"`.
+pub(crate) fn list_syntax_attr(meta: &syn::MetaNameValue) -> syn::Result<ListSyntax> {
+    match require_lit_str(meta, &meta.value)?.as_str() {
+        "plain" => Ok(ListSyntax::Plain),
+        "quoted" => Ok(ListSyntax::Quoted),
+        "json" => Ok(ListSyntax::Json),
+        _ => Err(syn::Error::new(
+            meta.span(),
+            "expected `list_syntax = \"plain\"`, `list_syntax = \"quoted\"` or `list_syntax = \"json\"`",
+        )),
+    }
+}
+
+/// Parse `empty = "unset"`, `empty = "error"` or `empty = "value"`.
+pub(crate) fn empty_policy(meta: &syn::MetaNameValue) -> syn::Result<EmptyPolicy> {
+    match require_lit_str(meta, &meta.value)?.as_str() {
+        "unset" => Ok(EmptyPolicy::Unset),
+        "error" => Ok(EmptyPolicy::Error),
+        "value" => Ok(EmptyPolicy::Value),
+        _ => Err(syn::Error::new(
+            meta.span(),
+            "expected `empty = \"unset\"`, `empty = \"error\"` or `empty = \"value\"`",
+        )),
+    }
+}
+
+/// Parse `trim` or `trim = false`.
+pub(crate) fn trim_attr(meta: &Meta) -> syn::Result<bool> {
+    let meta = match meta {
+        Meta::Path(_) => return Ok(true),
+        Meta::NameValue(meta) => meta,
+        Meta::List(_) => return Err(syn::Error::new_spanned(meta, "expected `trim = bool`")),
+    };
+    match &meta.value {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Bool(lit),
+            ..
+        }) => Ok(lit.value),
+        _ => Err(syn::Error::new_spanned(meta, "expected `trim = bool`")),
+    }
+}
+
+/// Parse `group(name = "...", exactly_one = [a, b])`.
+fn group(list: &syn::MetaList) -> syn::Result<EnvManGroup> {
+    let mut name: Option<String> = None;
+    let mut rule: Option<syn::Ident> = None;
+    let mut members: Vec<syn::Ident> = Vec::new();
+
+    // Parsed by hand, as `Meta` does not accept arrays of fields
+    list.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            check_duplicate!(meta.path.span(), name);
+
+            let lit: syn::LitStr = meta.value()?.parse()?;
+
+            name = Some(lit.value());
+            return Ok(());
+        }
+
+        let variant = if meta.path.is_ident("exactly_one") {
+            "ExactlyOne"
+        } else if meta.path.is_ident("at_least_one") {
+            "AtLeastOne"
+        } else if meta.path.is_ident("at_most_one") {
+            "AtMostOne"
+        } else {
+            return Err(meta.error("unexpected attribute"));
+        };
+        check_duplicate!(
+            meta.path.span(),
+            rule,
+            "use only one of `exactly_one`, `at_least_one` and `at_most_one`"
+        );
+
+        let value = meta.value()?;
+        let content;
+        syn::bracketed!(content in value);
+        members.extend(Punctuated::<syn::Ident, Token![,]>::parse_terminated(
+            &content,
+        )?);
+
+        rule = Some(syn::Ident::new(variant, meta.path.span()));
+        Ok(())
+    })?;
+
+    Ok(EnvManGroup {
+        name: name.ok_or_else(|| syn::Error::new_spanned(list, "`group` requires a `name`"))?,
+        rule: rule.ok_or_else(|| {
+            syn::Error::new_spanned(
+                list,
+                "`group` requires one of `exactly_one`, `at_least_one` and `at_most_one`",
+            )
+        })?,
+        members,
     })
 }
 